@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use baseview::{Size, WindowOpenOptions, WindowScalePolicy};
 use cozy_ui::util::get_set::Operation;
-use cozy_ui::widgets::button::toggle;
+use cozy_ui::widgets::button::{momentary, toggle, trigger};
 
 use cozy_ui::widgets::slider::slider;
 use cozy_ui::widgets::Knob;
@@ -168,6 +168,7 @@ struct TestApp {
     knob2: f32,
     button: bool,
     button2: bool,
+    hold: bool,
     show_about: bool,
     stopwatch: Sw,
     frame_history: FrameHistory,
@@ -182,6 +183,7 @@ impl Default for TestApp {
             knob2: Default::default(),
             button: false,
             button2: false,
+            hold: false,
             show_about: false,
             stopwatch: Sw::default(),
             frame_history: FrameHistory::default(),
@@ -241,6 +243,22 @@ impl TestApp {
                 )
                 .small(),
             );
+            ui.horizontal(|ui| {
+                ui.add(momentary(
+                    "hold",
+                    "hold",
+                    get_set(&mut self.hold),
+                    || {},
+                    || {},
+                ));
+                ui.add(trigger(
+                    "randomize",
+                    "randomize",
+                    || self.knob = ctx.input(|i| i.time).fract() as f32,
+                    || {},
+                    || {},
+                ));
+            });
             ui.add(
                 slider("slider1", get_set(&mut self.knob), || {}, || {})
                     .description("this is a slider.\ndo newlines work?")
@@ -1,11 +1,24 @@
 use std::time::Duration;
 
 use cozy_ui::centered;
+use cozy_ui::editor::ResizableEditor;
+use cozy_ui::layout::{grid, Alignment};
+use cozy_ui::presets::{FilesystemPresetStore, PresetStore};
+use cozy_ui::undo;
 use cozy_ui::util::get_set::Operation;
-use cozy_ui::widgets::button::toggle;
-use cozy_ui::widgets::knob::Knob;
+use cozy_ui::widgets::ab_comparison::{ab_comparison, SnapshotParameter};
+use cozy_ui::widgets::button::{checkbox, momentary, radio_group, toggle, trigger};
+use cozy_ui::widgets::encoder::encoder;
+use cozy_ui::widgets::group::group;
+use cozy_ui::widgets::knob::{DragMode, Knob, KnobStyle};
+use cozy_ui::widgets::number_box::number_box;
+use cozy_ui::widgets::piano::piano;
+use cozy_ui::widgets::preset_browser::preset_browser;
+use cozy_ui::widgets::selector::{dropdown, segmented};
 
 use cozy_ui::widgets::slider::slider;
+use cozy_ui::widgets::xy_pad::xy_pad;
+use cozy_ui::widgets::AutomationState;
 use egui::{include_image, CentralPanel, RichText, TopBottomPanel, Window};
 
 use egui::util::History;
@@ -137,6 +150,7 @@ impl FrameHistory {
 }
 
 const SAMPLES: usize = 1024;
+const WAVEFORMS: &[(u8, &str)] = &[(0, "sine"), (1, "saw"), (2, "square"), (3, "noise")];
 
 fn main() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
@@ -159,7 +173,15 @@ struct TestApp {
     knob2: f32,
     button: bool,
     button2: bool,
+    hold: bool,
+    waveform: u8,
+    phase: f32,
+    phase_bypass: bool,
+    bank: [f32; 6],
     show_about: bool,
+    show_presets: bool,
+    presets: Option<FilesystemPresetStore>,
+    current_preset: Option<String>,
     frame_history: FrameHistory,
     frame_idx: usize,
     frame_usages: [f32; SAMPLES],
@@ -172,7 +194,19 @@ impl Default for TestApp {
             knob2: Default::default(),
             button: false,
             button2: false,
+            hold: false,
+            waveform: 0,
+            phase: 0.0,
+            phase_bypass: false,
+            bank: [0.5; 6],
             show_about: false,
+            show_presets: false,
+            presets: FilesystemPresetStore::new(
+                std::env::temp_dir().join("cozy-ui-presets"),
+                "preset",
+            )
+            .ok(),
+            current_preset: None,
             frame_history: FrameHistory::default(),
             frame_idx: Default::default(),
             frame_usages: [0.0; SAMPLES],
@@ -184,33 +218,72 @@ impl eframe::App for TestApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         self.frame_history
             .on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
+        ResizableEditor::new(egui::vec2(320.0, 240.0)).show(ctx);
         TopBottomPanel::top("top").show(ctx, |ui| {
             if ui.button("About").clicked() {
                 self.show_about = !self.show_about;
             }
+            if ui.button("Presets").clicked() {
+                self.show_presets = !self.show_presets;
+            }
+            let (can_undo, can_redo) =
+                undo::with_history(ctx, |history| (history.can_undo(), history.can_redo()));
+            if ui
+                .add_enabled(can_undo, egui::Button::new("Undo"))
+                .clicked()
+            {
+                undo::undo(ctx);
+            }
+            if ui
+                .add_enabled(can_redo, egui::Button::new("Redo"))
+                .clicked()
+            {
+                undo::redo(ctx);
+            }
             ui.label(format!("{:?}", ctx.native_pixels_per_point()));
             ui.label(format!("{:?}", ctx.pixels_per_point()));
         });
         CentralPanel::default().show(ctx, |ui| {
+            ui.add(ab_comparison(
+                "ab",
+                &mut [
+                    SnapshotParameter::new("knob1", get_set(&mut self.knob), || {}, || {}),
+                    SnapshotParameter::new("knob2", get_set(&mut self.knob2), || {}, || {}),
+                ],
+            ));
             ui.horizontal(|ui| {
-                centered(ctx, ui, |ui| {
+                centered(ui, |ui| {
                     ui.add(
                         Knob::new("knob1", 50.0, get_set(&mut self.knob), || {}, || {})
                             .label("I GOT LABELS")
+                            .formatter(|value| format!("{:.0}%", value * 100.0))
+                            .truncate_label(true)
+                            .value_line(true)
                             .default_value(0.5),
                     );
                     ui.add(
                         Knob::new("knob2", 75.0, get_set(&mut self.knob2), || {}, || {})
+                            .style(KnobStyle::Arc)
                             .default_value(0.5)
-                            .modulated_value(0.75),
+                            .modulated_value(0.75)
+                            .infinite_drag(true),
                     );
-                    ui.add(
+                    ui.add_enabled(
+                        self.button,
                         Knob::new("knob3", 100.0, get_set(&mut self.knob), || {}, || {})
+                            .style(KnobStyle::Filled)
+                            .sweep(300.0)
+                            .drag_mode(DragMode::Rotary)
+                            .parameter_id("knob1")
                             .default_value(0.5),
                     );
                     ui.add(
                         Knob::new("knob4", 125.0, get_set(&mut self.knob2), || {}, || {})
+                            .style(KnobStyle::Minimal)
+                            .parameter_id("knob2")
                             .default_value(0.5)
+                            .automation(AutomationState::Automated)
+                            .locked(true)
                             .modulated_value(0.75),
                     );
                 })
@@ -229,11 +302,112 @@ impl eframe::App for TestApp {
                 || {},
                 || {},
             ));
+            ui.horizontal(|ui| {
+                ui.add(momentary(
+                    "hold",
+                    "hold",
+                    get_set(&mut self.hold),
+                    || {},
+                    || {},
+                ));
+                ui.add(trigger(
+                    "randomize",
+                    "randomize",
+                    || self.knob = ctx.input(|i| i.time).fract() as f32,
+                    || {},
+                    || {},
+                ));
+            });
+            group("phase", "Phase")
+                .bypass(get_set(&mut self.phase_bypass), || {}, || {})
+                .collapsible(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            encoder("phase", 50.0, get_set(&mut self.phase), || {}, || {})
+                                .label("phase"),
+                        );
+                        ui.add(
+                            encoder("steps", 50.0, get_set(&mut self.phase), || {}, || {})
+                                .parameter_id("phase")
+                                .detents(12)
+                                .label("stepped"),
+                        );
+                    });
+                });
+            grid("bank", egui::vec2(60.0, 70.0))
+                .max_columns(4)
+                .horizontal_align(Alignment::Center)
+                .show(ui, self.bank.len(), |ui, index| {
+                    let id = format!("bank{index}");
+                    ui.add(
+                        Knob::new(&id, 40.0, get_set(&mut self.bank[index]), || {}, || {})
+                            .label(format!("band {}", index + 1))
+                            .truncate_label(true),
+                    );
+                });
             ui.add(
                 slider("slider1", get_set(&mut self.knob), || {}, || {})
+                    .parameter_id("knob1")
                     .description("this is a slider.\ndo newlines work?")
                     .default_value(0.5),
             );
+            ui.add(
+                number_box("bpm", get_set(&mut self.knob), || {}, || {})
+                    .parameter_id("knob1")
+                    .formatter(|value| format!("{:.1} BPM", value * 280.0 + 20.0))
+                    .parser(|text| {
+                        text.trim_end_matches("BPM")
+                            .trim()
+                            .parse::<f32>()
+                            .ok()
+                            .map(|bpm| (bpm - 20.0) / 280.0)
+                    }),
+            );
+            ui.add(checkbox(
+                "checkbox",
+                "checkbox",
+                get_set(&mut self.button2),
+                || {},
+                || {},
+            ));
+            ui.add(
+                radio_group(
+                    "waveform_radio",
+                    WAVEFORMS,
+                    get_set(&mut self.waveform),
+                    || {},
+                    || {},
+                )
+                .horizontal(),
+            );
+            ui.add(segmented(
+                "waveform",
+                WAVEFORMS,
+                get_set(&mut self.waveform),
+                || {},
+                || {},
+            ));
+            ui.add(dropdown(
+                "waveform_dropdown",
+                WAVEFORMS,
+                get_set(&mut self.waveform),
+                || {},
+                || {},
+            ));
+            ui.add(
+                xy_pad(
+                    "xy_pad1",
+                    egui::vec2(150.0, 150.0),
+                    get_set(&mut self.knob),
+                    get_set(&mut self.knob2),
+                    || {},
+                    || {},
+                )
+                .default_value(0.5, 0.5)
+                .modulated_value(0.75, 0.25),
+            );
+            ui.add(piano("piano1", |_, _| {}, |_| {}).computer_keyboard(true));
             ui.label(format!("fps: {}", self.frame_history.fps()));
             if let Some(usage) = frame.info().cpu_usage {
                 self.frame_usages[self.frame_idx] = usage;
@@ -247,10 +421,57 @@ impl eframe::App for TestApp {
         Window::new("About")
             .open(&mut self.show_about)
             .show(ctx, |ui| {
-                ui.image(include_image!("Cozy_logo.png"));
-                ui.heading(RichText::new("Example Plugin").strong());
-                ui.label(RichText::new("version here").italics());
-                ui.hyperlink_to("GitHub", "https://crouton.net");
+                centered(ui, |ui| {
+                    ui.image(include_image!("Cozy_logo.png"));
+                    ui.heading(RichText::new("Example Plugin").strong());
+                    ui.label(RichText::new("version here").italics());
+                    ui.hyperlink_to("GitHub", "https://crouton.net");
+                });
+            });
+        Window::new("Centered")
+            .default_size(egui::vec2(300.0, 200.0))
+            .resizable(true)
+            .show(ctx, |ui| {
+                centered(ui, |ui| {
+                    ui.label("stays in the middle while the window is resized");
+                    ui.menu_button("popup", |ui| {
+                        centered(ui, |ui| {
+                            ui.label("shrinks to fit");
+                            ui.label("a popup around centered contents");
+                        });
+                    });
+                });
+            });
+        Window::new("Presets")
+            .open(&mut self.show_presets)
+            .show(ctx, |ui| {
+                let Some(store) = &mut self.presets else {
+                    ui.label("couldn't open the preset folder");
+                    return;
+                };
+                if ui.button("save knob 1").clicked() {
+                    let name = format!("knob at {:.2}", self.knob);
+                    if let Ok(id) = store.save(&name, &[], self.knob.to_string().as_bytes()) {
+                        self.current_preset = Some(id);
+                    }
+                }
+                let mut loaded = None;
+                let knob = &mut self.knob;
+                ui.add(
+                    preset_browser("presets", store, |preset, data| {
+                        if let Some(value) = String::from_utf8(data)
+                            .ok()
+                            .and_then(|data| data.parse().ok())
+                        {
+                            *knob = value;
+                        }
+                        loaded = Some(preset.id.clone());
+                    })
+                    .current(self.current_preset.as_deref()),
+                );
+                if loaded.is_some() {
+                    self.current_preset = loaded;
+                }
             });
         ctx.request_repaint();
     }
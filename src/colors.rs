@@ -4,6 +4,10 @@ pub const BACKGROUND: (u8, u8, u8) = (69, 65, 73);
 pub const WIDGET_BACKGROUND: (u8, u8, u8) = (75, 54, 78);
 pub const HIGHLIGHT: (u8, u8, u8) = (255, 45, 128);
 pub const PURPLE: (u8, u8, u8) = (118, 72, 151);
+pub const AUTOMATION_READ: (u8, u8, u8) = (72, 201, 142);
+pub const AUTOMATION_WRITE: (u8, u8, u8) = (255, 84, 64);
+pub const AUTOMATION_OVERRIDE: (u8, u8, u8) = (255, 184, 48);
+pub const MODULATION: (u8, u8, u8) = (175, 48, 255);
 
 pub const PURPLE_COL32: Color32 = Color32::from_rgb(PURPLE.0, PURPLE.1, PURPLE.2);
 pub const HIGHLIGHT_COL32: Color32 = Color32::from_rgb(HIGHLIGHT.0, HIGHLIGHT.1, HIGHLIGHT.2);
@@ -12,3 +16,13 @@ pub const WIDGET_BACKGROUND_COL32: Color32 = Color32::from_rgb(
     WIDGET_BACKGROUND.1,
     WIDGET_BACKGROUND.2,
 );
+pub const AUTOMATION_READ_COL32: Color32 =
+    Color32::from_rgb(AUTOMATION_READ.0, AUTOMATION_READ.1, AUTOMATION_READ.2);
+pub const AUTOMATION_WRITE_COL32: Color32 =
+    Color32::from_rgb(AUTOMATION_WRITE.0, AUTOMATION_WRITE.1, AUTOMATION_WRITE.2);
+pub const AUTOMATION_OVERRIDE_COL32: Color32 = Color32::from_rgb(
+    AUTOMATION_OVERRIDE.0,
+    AUTOMATION_OVERRIDE.1,
+    AUTOMATION_OVERRIDE.2,
+);
+pub const MODULATION_COL32: Color32 = Color32::from_rgb(MODULATION.0, MODULATION.1, MODULATION.2);
@@ -0,0 +1,148 @@
+use egui::{
+    vec2, Align2, Area, Context, Id, Order, Rect, Sense, Stroke, Ui, Vec2, ViewportCommand,
+};
+
+use crate::colors::PURPLE_COL32;
+
+const ZOOM_ID: &str = "cozy_ui_editor_zoom";
+
+pub const MIN_ZOOM: f32 = 0.75;
+pub const MAX_ZOOM: f32 = 2.0;
+
+/// the zoom levels offered in the resize handle's right click menu
+const ZOOM_PRESETS: [f32; 6] = [0.75, 1.0, 1.25, 1.5, 1.75, 2.0];
+
+const HANDLE_SIZE: f32 = 16.0;
+
+type ResizeCallback<'a> = Box<dyn FnMut(Vec2) + 'a>;
+
+/// The zoom the user picked for the editor, `1.0` being 100%.
+///
+/// It's kept in egui's persisted memory, so it survives reopening the editor as long as the integration saves egui's memory
+#[must_use]
+pub fn zoom(ctx: &Context) -> f32 {
+    ctx.data_mut(|data| data.get_persisted(Id::new(ZOOM_ID)))
+        .unwrap_or(1.0)
+}
+
+/// Sets the editor's zoom, clamped between [`MIN_ZOOM`] and [`MAX_ZOOM`], and applies it to `ctx`
+pub fn set_zoom(ctx: &Context, zoom: f32) {
+    let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    ctx.data_mut(|data| data.insert_persisted(Id::new(ZOOM_ID), zoom));
+    ctx.set_zoom_factor(zoom);
+}
+
+/// Scales a plugin editor with the user's zoom and draws a handle in the bottom right corner to resize it.
+///
+/// The editor is designed at `size` and always keeps its aspect ratio, it just gets zoomed in or out. Hosts
+/// resize plugin windows themselves, so the new window size is handed to [`ResizableEditor::on_resize`] for the
+/// integration to pass on (e.g. to the window handler of `egui-baseview`). On a native viewport the window is
+/// asked to resize as well
+#[must_use]
+pub struct ResizableEditor<'a> {
+    size: Vec2,
+    on_resize: Option<ResizeCallback<'a>>,
+}
+
+impl<'a> ResizableEditor<'a> {
+    pub const fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            on_resize: None,
+        }
+    }
+
+    /// Calls `on_resize` with the new window size whenever the zoom changes. The size is in the window system's
+    /// logical pixels, before egui's zoom is applied, so it's the design size times the zoom
+    pub fn on_resize(mut self, on_resize: impl FnMut(Vec2) + 'a) -> Self {
+        self.on_resize = Some(Box::new(on_resize));
+        self
+    }
+
+    /// Applies the zoom and shows the resize handle. Call this at the start of every frame, before the panels
+    pub fn show(mut self, ctx: &Context) {
+        let zoom = zoom(ctx);
+        if (ctx.zoom_factor() - zoom).abs() > f32::EPSILON {
+            ctx.set_zoom_factor(zoom);
+        }
+
+        let screen_rect = ctx.screen_rect();
+        let new_zoom = Area::new(Id::new("cozy_ui_editor_resize_handle"))
+            .order(Order::Foreground)
+            .fixed_pos(screen_rect.right_bottom())
+            .pivot(Align2::RIGHT_BOTTOM)
+            .show(ctx, |ui| {
+                let (rect, response) =
+                    ui.allocate_exact_size(Vec2::splat(HANDLE_SIZE), Sense::drag());
+                let response = response.on_hover_cursor(egui::CursorIcon::ResizeNwSe);
+                paint_handle(ui, rect, response.hovered() || response.dragged());
+
+                let mut new_zoom = None;
+                if response.dragged() {
+                    if let Some(pointer) = ui.input(|input| input.pointer.latest_pos()) {
+                        // the pointer is in points, which shrink and grow with the zoom. the window's top left is the origin
+                        let window_size = (pointer - screen_rect.min) * zoom;
+                        let scale = window_size / self.size;
+                        // keeping the aspect ratio means the handle can't follow the pointer on both axes,
+                        // so it goes halfway between
+                        new_zoom = Some((scale.x + scale.y) * 0.5);
+                    }
+                }
+
+                response.context_menu(|ui| {
+                    for preset in ZOOM_PRESETS {
+                        if ui
+                            .selectable_label(
+                                (zoom - preset).abs() < 0.005,
+                                format!("{:.0}%", preset * 100.0),
+                            )
+                            .clicked()
+                        {
+                            new_zoom = Some(preset);
+                            ui.close_menu();
+                        }
+                    }
+                });
+
+                new_zoom
+            })
+            .inner;
+
+        let Some(new_zoom) = new_zoom else {
+            return;
+        };
+        // whole percents, so dragging doesn't send the host a stream of tiny resizes
+        let new_zoom = ((new_zoom * 100.0).round() / 100.0).clamp(MIN_ZOOM, MAX_ZOOM);
+        if (new_zoom - zoom).abs() < f32::EPSILON {
+            return;
+        }
+
+        set_zoom(ctx, new_zoom);
+        // viewport sizes are in points, which the integration scales by the zoom itself
+        ctx.send_viewport_cmd(ViewportCommand::InnerSize(self.size));
+        if let Some(on_resize) = &mut self.on_resize {
+            on_resize(self.size * new_zoom);
+        }
+    }
+}
+
+/// the three diagonal grip lines in the corner
+fn paint_handle(ui: &Ui, rect: Rect, hovered: bool) {
+    let color = if hovered {
+        ui.visuals().strong_text_color()
+    } else {
+        PURPLE_COL32
+    };
+    let painter = ui.painter();
+    for step in 1..=3 {
+        #[allow(clippy::cast_precision_loss)]
+        let offset = HANDLE_SIZE * step as f32 / 4.0;
+        painter.line_segment(
+            [
+                rect.right_bottom() - vec2(offset, 0.0),
+                rect.right_bottom() - vec2(0.0, offset),
+            ],
+            Stroke::new(1.5, color),
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use egui::{Context, Id, Modifiers};
+
+const INTERACTION_SETTINGS_ID: &str = "cozy_ui_interaction_settings";
+
+/// How a widget is reset to its default value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResetGesture {
+    #[default]
+    DoubleClick,
+    /// Ctrl-click, or Cmd-click on macOS
+    CommandClick,
+}
+
+/// How the widgets respond to the pointer and the scroll wheel, shared by every widget in the context.
+///
+/// Sensitivities are in points, so a knob feels the same whatever its size
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InteractionSettings {
+    /// How far the pointer has to be dragged to go through the whole range
+    pub pixels_per_range: f32,
+    /// How many times slower dragging and scrolling get while the fine modifier is held
+    pub fine_factor: f32,
+    /// The modifier that switches to fine adjustment
+    pub fine_modifier: Modifiers,
+    /// How much of the range one notch of the scroll wheel moves through
+    pub scroll_step: f32,
+    pub reset_gesture: ResetGesture,
+}
+
+impl Default for InteractionSettings {
+    fn default() -> Self {
+        Self {
+            pixels_per_range: 200.0,
+            fine_factor: 2.0,
+            fine_modifier: Modifiers::SHIFT,
+            scroll_step: 0.05,
+            reset_gesture: ResetGesture::DoubleClick,
+        }
+    }
+}
+
+/// Runs `f` with the interaction settings stored in `ctx`
+pub fn with_interaction_settings<R>(
+    ctx: &Context,
+    f: impl FnOnce(&mut InteractionSettings) -> R,
+) -> R {
+    ctx.data_mut(|data| f(data.get_temp_mut_or_default(Id::new(INTERACTION_SETTINGS_ID))))
+}
+
+/// The interaction settings stored in `ctx`
+#[must_use]
+pub fn interaction_settings(ctx: &Context) -> InteractionSettings {
+    with_interaction_settings(ctx, |settings| *settings)
+}
+
+/// Replaces the interaction settings stored in `ctx`
+pub fn set_interaction_settings(ctx: &Context, settings: InteractionSettings) {
+    with_interaction_settings(ctx, |current| *current = settings);
+}
@@ -0,0 +1,197 @@
+use std::hash::Hash;
+
+use egui::{vec2, Align, Id, InnerResponse, Layout, Rect, Sense, Ui, Vec2};
+
+/// How the cells of a [`Grid`] are spread out along an axis
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// The first and last cells touch the edges and the leftover space goes between the cells
+    SpaceBetween,
+}
+
+impl Alignment {
+    /// where the first of `count` cells goes and how far apart the cells start, given `free` space left over
+    #[allow(clippy::cast_precision_loss)]
+    fn place(self, free: f32, count: usize, cell: f32, spacing: f32) -> (f32, f32) {
+        let free = free.max(0.0);
+        match self {
+            Self::SpaceBetween if count > 1 => (0.0, cell + spacing + free / (count - 1) as f32),
+            Self::Start => (0.0, cell + spacing),
+            // a single cell has nothing to spread out from, so it's centered
+            Self::Center | Self::SpaceBetween => (free / 2.0, cell + spacing),
+            Self::End => (free, cell + spacing),
+        }
+    }
+}
+
+pub fn grid(id_source: impl Hash, cell_size: Vec2) -> Grid {
+    Grid::new(id_source, cell_size)
+}
+
+/// Lays out same-sized cells in rows, wrapping to a new row when the next cell doesn't fit. Meant for banks of knobs.
+///
+/// The grid works in any [`Ui`], it only uses the space it's given
+#[must_use]
+pub struct Grid {
+    id: Id,
+    cell_size: Vec2,
+    spacing: Option<Vec2>,
+    max_columns: Option<usize>,
+    horizontal: Alignment,
+    vertical: Alignment,
+}
+
+impl Grid {
+    pub fn new(id_source: impl Hash, cell_size: Vec2) -> Self {
+        Self {
+            id: Id::new(id_source),
+            cell_size,
+            spacing: None,
+            max_columns: None,
+            horizontal: Alignment::Start,
+            vertical: Alignment::Start,
+        }
+    }
+
+    /// Sets the space between cells. Defaults to the `Ui`'s item spacing
+    pub const fn spacing(mut self, spacing: Vec2) -> Self {
+        self.spacing = Some(spacing);
+        self
+    }
+
+    /// Wraps after `columns` cells even if there's room for more
+    pub const fn max_columns(mut self, columns: usize) -> Self {
+        self.max_columns = Some(columns);
+        self
+    }
+
+    /// How each row is spread out across the available width. Centered rows are each centered on their own,
+    /// so a short last row sits in the middle
+    pub const fn horizontal_align(mut self, align: Alignment) -> Self {
+        self.horizontal = align;
+        self
+    }
+
+    /// How the rows are spread out across the available height. Anything other than [`Alignment::Start`]
+    /// takes up all the available height, unless it's unbounded like in a scroll area
+    pub const fn vertical_align(mut self, align: Alignment) -> Self {
+        self.vertical = align;
+        self
+    }
+
+    /// Shows `count` cells, calling `add_cell` with the index of each one. Cell contents are centered horizontally
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn show<R>(
+        self,
+        ui: &mut Ui,
+        count: usize,
+        mut add_cell: impl FnMut(&mut Ui, usize) -> R,
+    ) -> InnerResponse<Vec<R>> {
+        let spacing = self.spacing.unwrap_or_else(|| ui.spacing().item_spacing);
+        let available = ui.available_rect_before_wrap();
+        let cell = self.cell_size;
+
+        let fitting_columns = if available.width().is_finite() {
+            ((available.width() + spacing.x) / (cell.x + spacing.x)).floor() as usize
+        } else {
+            count
+        };
+        let columns = fitting_columns
+            .min(self.max_columns.unwrap_or(usize::MAX))
+            .min(count)
+            .max(1);
+        let rows = count.div_ceil(columns);
+
+        let row_width = |cells: usize| (cells as f32).mul_add(cell.x + spacing.x, -spacing.x);
+        let width = if available.width().is_finite() {
+            available.width().max(row_width(columns))
+        } else {
+            row_width(columns)
+        };
+        let content_height = (rows as f32)
+            .mul_add(cell.y + spacing.y, -spacing.y)
+            .max(0.0);
+        let height = if self.vertical == Alignment::Start || !available.height().is_finite() {
+            content_height
+        } else {
+            available.height().max(content_height)
+        };
+        let rect = Rect::from_min_size(available.min, vec2(width, height));
+
+        let (top, row_step) = self
+            .vertical
+            .place(height - content_height, rows, cell.y, spacing.y);
+        let mut inner = Vec::with_capacity(count);
+        for row in 0..rows {
+            let first = row * columns;
+            let cells = columns.min(count - first);
+            // space-between keeps the columns lined up, so a short last row is spread out like a full one
+            let spread = if self.horizontal == Alignment::SpaceBetween {
+                columns
+            } else {
+                cells
+            };
+            let (left, column_step) =
+                self.horizontal
+                    .place(width - row_width(spread), spread, cell.x, spacing.x);
+
+            for column in 0..cells {
+                let min = rect.min
+                    + vec2(
+                        (column as f32).mul_add(column_step, left),
+                        (row as f32).mul_add(row_step, top),
+                    );
+                let mut cell_ui = ui.child_ui_with_id_source(
+                    Rect::from_min_size(min, cell),
+                    Layout::top_down(Align::Center),
+                    self.id.with(first + column),
+                    None,
+                );
+                inner.push(add_cell(&mut cell_ui, first + column));
+            }
+        }
+
+        let response = ui.allocate_rect(rect, Sense::hover());
+        InnerResponse::new(inner, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Alignment;
+
+    fn place(align: Alignment, free: f32, count: usize) -> (f32, f32) {
+        align.place(free, count, 50.0, 10.0)
+    }
+
+    #[test]
+    fn start_center_and_end_only_move_the_first_cell() {
+        assert_eq!(place(Alignment::Start, 40.0, 3), (0.0, 60.0));
+        assert_eq!(place(Alignment::Center, 40.0, 3), (20.0, 60.0));
+        assert_eq!(place(Alignment::End, 40.0, 3), (40.0, 60.0));
+    }
+
+    #[test]
+    fn space_between_spreads_the_free_space_between_cells() {
+        assert_eq!(place(Alignment::SpaceBetween, 40.0, 3), (0.0, 80.0));
+    }
+
+    #[test]
+    fn space_between_centers_a_single_cell() {
+        assert_eq!(place(Alignment::SpaceBetween, 40.0, 1), (20.0, 60.0));
+    }
+
+    #[test]
+    fn overflowing_cells_start_at_the_edge() {
+        assert_eq!(place(Alignment::Center, -40.0, 3), (0.0, 60.0));
+        assert_eq!(place(Alignment::SpaceBetween, -40.0, 3), (0.0, 60.0));
+    }
+}
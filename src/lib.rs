@@ -1,15 +1,31 @@
 #![warn(clippy::pedantic)]
 #![warn(clippy::nursery)]
 
+use std::sync::Arc;
+
 use colors::{BACKGROUND, WIDGET_BACKGROUND_COL32};
-use egui::{epaint::Shadow, vec2, Color32, Context, InnerResponse, Ui};
+use egui::{epaint::Shadow, vec2, Color32, Context, InnerResponse, Rect, Sense, Ui, Vec2};
 
 pub mod util;
 
+pub mod presets;
+
 pub mod widgets;
 
 pub mod colors;
 
+pub mod editor;
+
+pub mod layout;
+
+pub mod interaction;
+
+pub mod midi;
+
+pub mod registry;
+
+pub mod undo;
+
 pub fn setup(ctx: &Context) {
     ctx.style_mut(|style| {
         style.visuals.popup_shadow = Shadow {
@@ -24,23 +40,107 @@ pub fn setup(ctx: &Context) {
         style.visuals.widgets.active.weak_bg_fill = WIDGET_BACKGROUND_COL32;
         style.visuals.interact_cursor = Some(egui::CursorIcon::PointingHand);
     });
+    ctx.on_begin_frame("cozy_ui_undo", Arc::new(undo::handle_shortcuts));
+    ctx.on_begin_frame(
+        "cozy_ui_scroll_gestures",
+        Arc::new(widgets::end_idle_scroll_gestures),
+    );
+    ctx.on_begin_frame(
+        "cozy_ui_pianos",
+        Arc::new(widgets::piano::release_hidden_pianos),
+    );
+    ctx.on_begin_frame(
+        "cozy_ui_momentary_buttons",
+        Arc::new(widgets::button::release_hidden_momentary_buttons),
+    );
+}
+
+/// the size of the contents of a [`centered`] ui, and of the space it took up, as of last frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CenteredState {
+    content: Vec2,
+    used: Vec2,
+}
+
+/// centers `add_contents` in the available space, on both axes. an axis without a limit, like the scrolling
+/// axis of a scroll area, isn't centered.
+///
+/// the contents are measured the first time they're shown, in an invisible sizing pass, and the size is
+/// remembered from then on. if the size changes the contents are off center for one frame.
+///
+/// only the contents and the space before them are taken up, so auto-sized windows, areas and popups can still
+/// shrink to fit. nothing is centered while such a container is measuring its contents or shrink-wrapping them
+pub fn centered<R>(ui: &mut Ui, add_contents: impl FnOnce(&mut Ui) -> R) -> InnerResponse<R> {
+    let id = ui.next_auto_id().with("cozy_ui_centered");
+    let available = ui.available_rect_before_wrap();
+    let state = ui.memory(|mem| mem.data.get_temp::<CenteredState>(id));
+
+    let child_rect = match state {
+        Some(state) if !ui.is_sizing_pass() => {
+            let offset = vec2(
+                center_offset(available.width(), state.content.x, state.used.x),
+                center_offset(available.height(), state.content.y, state.used.y),
+            );
+            Rect::from_min_size(available.min + offset, state.content)
+        }
+        _ => available,
+    };
+
+    let mut child_ui = ui.child_ui(child_rect, *ui.layout(), None);
+    if state.is_none() {
+        child_ui.set_sizing_pass();
+    }
+    let inner = add_contents(&mut child_ui);
+    let content = child_ui.min_rect();
+
+    let used = Rect::from_min_max(available.min, content.max);
+    let new_state = CenteredState {
+        content: content.size(),
+        used: used.size(),
+    };
+    if state != Some(new_state) {
+        ui.memory_mut(|mem| mem.data.insert_temp(id, new_state));
+        ui.ctx().request_repaint();
+    }
+
+    let response = ui.allocate_rect(used, Sense::hover());
+    InnerResponse::new(inner, response)
 }
 
-/// centers a ui in the available space. this will not work properly with anything that's not in a panel.
-pub fn centered<R>(
-    ctx: &Context,
-    ui: &mut Ui,
-    add_contents: impl FnOnce(&mut Ui) -> R,
-) -> InnerResponse<R> {
-    let layer_id = egui::LayerId::new(egui::Order::PanelResizeLine, ui.next_auto_id());
-    ui.with_layer_id(layer_id, |ui| {
-        let resp = add_contents(ui);
-
-        let shift_x = ui.available_width() / 2.0;
-        ctx.set_transform_layer(
-            layer_id,
-            egui::emath::TSTransform::from_translation(egui::vec2(shift_x, 0.0)),
-        );
-        resp
-    })
+/// how far into `available` space contents of `size` have to start to be centered. unlimited space isn't centered,
+/// and neither is space that's exactly what was `used` last frame, since that's a container shrink-wrapping its
+/// contents and centering in it would keep it from ever shrinking
+fn center_offset(available: f32, size: f32, used: f32) -> f32 {
+    if !available.is_finite() || (available - used).abs() < 0.5 {
+        return 0.0;
+    }
+    ((available - size) / 2.0).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::center_offset;
+
+    #[test]
+    fn contents_are_centered_in_the_leftover_space() {
+        assert!((center_offset(300.0, 100.0, 200.0) - 100.0).abs() < f32::EPSILON);
+        assert!((center_offset(300.0, 100.0, 100.0) - 100.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn contents_bigger_than_the_space_start_at_the_edge() {
+        assert!(center_offset(100.0, 300.0, 300.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn unlimited_space_is_not_centered() {
+        assert!(center_offset(f32::INFINITY, 100.0, 100.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn shrink_wrapping_containers_are_not_centered() {
+        // an auto-sized window offers exactly the space that was used last frame
+        assert!(center_offset(200.0, 100.0, 200.0).abs() < f32::EPSILON);
+        assert!(center_offset(200.2, 100.0, 200.0).abs() < f32::EPSILON);
+    }
 }
@@ -0,0 +1,98 @@
+use std::{collections::HashMap, fmt::Display};
+
+use egui::{Context, Id};
+
+use crate::registry;
+
+const MIDI_LEARN_ID: &str = "cozy_ui_midi_learn";
+
+/// A MIDI CC controller on a channel. Channels count from 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ControlChange {
+    pub channel: u8,
+    pub cc: u8,
+}
+
+impl Display for ControlChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CC {} (ch {})", self.cc, self.channel + 1)
+    }
+}
+
+/// Which CC controls which parameter, and which parameter (if any) is waiting to be bound.
+///
+/// The UI only puts parameters into learn mode, the plugin has to feed it its incoming CC messages
+/// with [`handle_cc`]. Mapped CCs set their parameter through the [`registry`], so the widget showing it
+/// brackets the change with `begin_set`/`end_set` like any other edit
+#[derive(Clone, Debug, Default)]
+pub struct MidiLearn {
+    learning: Option<String>,
+    mappings: HashMap<ControlChange, String>,
+}
+
+impl MidiLearn {
+    /// Puts `parameter` in learn mode. The next CC that comes in gets bound to it
+    pub fn learn(&mut self, parameter: &str) {
+        self.learning = Some(parameter.to_owned());
+    }
+
+    pub fn cancel_learn(&mut self) {
+        self.learning = None;
+    }
+
+    #[must_use]
+    pub fn learning(&self) -> Option<&str> {
+        self.learning.as_deref()
+    }
+
+    /// The CC bound to `parameter`, if any
+    #[must_use]
+    pub fn mapping(&self, parameter: &str) -> Option<ControlChange> {
+        self.mappings
+            .iter()
+            .find(|(_, mapped)| *mapped == parameter)
+            .map(|(cc, _)| *cc)
+    }
+
+    #[must_use]
+    pub const fn mappings(&self) -> &HashMap<ControlChange, String> {
+        &self.mappings
+    }
+
+    /// Binds `cc` to `parameter`, replacing whatever either was bound to before
+    pub fn map(&mut self, cc: ControlChange, parameter: &str) {
+        self.clear_mapping(parameter);
+        self.mappings.insert(cc, parameter.to_owned());
+    }
+
+    pub fn clear_mapping(&mut self, parameter: &str) {
+        self.mappings.retain(|_, mapped| mapped != parameter);
+    }
+}
+
+/// Runs `f` with the MIDI learn state stored in `ctx`
+pub fn with_midi_learn<R>(ctx: &Context, f: impl FnOnce(&mut MidiLearn) -> R) -> R {
+    ctx.data_mut(|data| f(data.get_temp_mut_or_default(Id::new(MIDI_LEARN_ID))))
+}
+
+/// Feeds an incoming CC message to the UI, `value` being the normalized CC value.
+///
+/// If a parameter is in learn mode the CC is bound to it, otherwise the parameter the CC is mapped to is set
+pub fn handle_cc(ctx: &Context, cc: ControlChange, value: f32) {
+    let parameter = with_midi_learn(ctx, |midi_learn| {
+        if let Some(parameter) = midi_learn.learning.take() {
+            midi_learn.map(cc, &parameter);
+            Some(parameter)
+        } else {
+            midi_learn.mappings.get(&cc).cloned()
+        }
+    });
+
+    if let Some(parameter) = parameter {
+        registry::request_set(ctx, &parameter, value.clamp(0.0, 1.0));
+    }
+}
+
+pub(crate) fn is_learning(ctx: &Context, parameter: &str) -> bool {
+    with_midi_learn(ctx, |midi_learn| midi_learn.learning() == Some(parameter))
+}
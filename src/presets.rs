@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+pub mod filesystem;
+
+pub use filesystem::FilesystemPresetStore;
+
+/// A preset as the browser sees it. The preset's data stays in the store until it's loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preset {
+    /// Uniquely identifies the preset within its store
+    pub id: String,
+    pub name: String,
+    /// The category path, outermost first (e.g. `["Bass", "Sub"]`). Empty if the preset isn't categorized
+    pub category: Vec<String>,
+    pub favorite: bool,
+}
+
+impl Preset {
+    /// Whether the preset lives in `category` or any category below it
+    #[must_use]
+    pub fn in_category(&self, category: &[String]) -> bool {
+        self.category.starts_with(category)
+    }
+}
+
+/// Somewhere presets are kept. The data format is up to the plugin, the store only moves bytes around.
+/// Every fallible method returns the store's own error, which the preset browser shows to the user
+#[allow(clippy::missing_errors_doc)]
+pub trait PresetStore {
+    type Error: Display;
+
+    /// All presets in the store, in the order they should be browsed
+    fn presets(&self) -> &[Preset];
+
+    /// Re-reads the presets from wherever they're kept
+    fn refresh(&mut self) -> Result<(), Self::Error>;
+
+    fn load(&mut self, id: &str) -> Result<Vec<u8>, Self::Error>;
+
+    /// Saves `data` under `name` in `category`, replacing any preset already there, and returns the new preset's id
+    fn save(&mut self, name: &str, category: &[String], data: &[u8])
+        -> Result<String, Self::Error>;
+
+    fn set_favorite(&mut self, id: &str, favorite: bool) -> Result<(), Self::Error>;
+}
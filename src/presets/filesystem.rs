@@ -0,0 +1,246 @@
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use super::{Preset, PresetStore};
+
+/// a dot-file never has an extension, so it can't be mistaken for a preset whatever the store's extension is
+const FAVORITES_FILE: &str = ".favorites";
+
+/// Keeps presets as files under a root directory. Subdirectories are categories,
+/// and favorites are listed one id per line in `.favorites` in the root
+pub struct FilesystemPresetStore {
+    root: PathBuf,
+    extension: String,
+    presets: Vec<Preset>,
+}
+
+impl FilesystemPresetStore {
+    /// Creates a store for the files ending in `extension` under `root`, creating `root` if it doesn't exist yet
+    ///
+    /// # Errors
+    ///
+    /// Fails if `root` can't be created or read
+    pub fn new(root: impl Into<PathBuf>, extension: impl Into<String>) -> io::Result<Self> {
+        let mut store = Self {
+            root: root.into(),
+            extension: extension.into(),
+            presets: Vec::new(),
+        };
+        fs::create_dir_all(&store.root)?;
+        store.refresh()?;
+        Ok(store)
+    }
+
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// the path of the preset `id`, making sure it stays under the root
+    fn path_of(&self, id: &str) -> io::Result<PathBuf> {
+        id.split('/')
+            .try_fold(self.root.clone(), |path, component| {
+                check_component(component)?;
+                Ok(path.join(component))
+            })
+    }
+
+    fn read_favorites(&self) -> io::Result<BTreeSet<String>> {
+        match fs::read_to_string(self.root.join(FAVORITES_FILE)) {
+            Ok(favorites) => Ok(favorites
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_owned)
+                .collect()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn write_favorites(&self) -> io::Result<()> {
+        let favorites = self.presets.iter().filter(|preset| preset.favorite).fold(
+            String::new(),
+            |mut favorites, preset| {
+                favorites.push_str(&preset.id);
+                favorites.push('\n');
+                favorites
+            },
+        );
+        fs::write(self.root.join(FAVORITES_FILE), favorites)
+    }
+
+    fn scan(&self, dir: &Path, category: &[String], presets: &mut Vec<Preset>) -> io::Result<()> {
+        let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(fs::DirEntry::file_name);
+
+        for entry in entries {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+
+            if entry.file_type()?.is_dir() {
+                let mut category = category.to_vec();
+                category.push(file_name);
+                self.scan(&path, &category, presets)?;
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == self.extension.as_str())
+            {
+                let name = path
+                    .file_stem()
+                    .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+                presets.push(Preset {
+                    id: id_of(category, &file_name),
+                    name,
+                    category: category.to_vec(),
+                    favorite: false,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PresetStore for FilesystemPresetStore {
+    type Error = io::Error;
+
+    fn presets(&self) -> &[Preset] {
+        &self.presets
+    }
+
+    fn refresh(&mut self) -> Result<(), Self::Error> {
+        let mut presets = Vec::new();
+        self.scan(&self.root, &[], &mut presets)?;
+
+        let favorites = self.read_favorites()?;
+        for preset in &mut presets {
+            preset.favorite = favorites.contains(&preset.id);
+        }
+
+        self.presets = presets;
+        Ok(())
+    }
+
+    fn load(&mut self, id: &str) -> Result<Vec<u8>, Self::Error> {
+        fs::read(self.path_of(id)?)
+    }
+
+    fn save(
+        &mut self,
+        name: &str,
+        category: &[String],
+        data: &[u8],
+    ) -> Result<String, Self::Error> {
+        check_component(name)?;
+        for component in category {
+            check_component(component)?;
+        }
+        let file_name = format!("{name}.{}", self.extension);
+        let id = id_of(category, &file_name);
+        let path = self.path_of(&id)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, data)?;
+        self.refresh()?;
+
+        Ok(id)
+    }
+
+    fn set_favorite(&mut self, id: &str, favorite: bool) -> Result<(), Self::Error> {
+        if let Some(preset) = self.presets.iter_mut().find(|preset| preset.id == id) {
+            preset.favorite = favorite;
+        }
+        self.write_favorites()
+    }
+}
+
+/// ids are paths relative to the root, always `/` separated so favorites files are portable
+fn id_of(category: &[String], file_name: &str) -> String {
+    category
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(file_name))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// names and categories become single path components, so anything that would step out of its directory
+/// (`..`, separators, absolute paths) is refused
+fn check_component(component: &str) -> io::Result<()> {
+    let mut components = Path::new(component).components();
+    let single =
+        matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+    // `/` separates ids and `\` separates paths on windows, so neither is allowed on any platform
+    if single && !component.contains(['/', '\\']) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid preset name or category {component:?}"),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> FilesystemPresetStore {
+        FilesystemPresetStore {
+            root: PathBuf::from("presets"),
+            extension: "preset".to_owned(),
+            presets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn check_component_accepts_plain_names() {
+        assert!(check_component("Warm Pad").is_ok());
+        assert!(check_component("bass.preset").is_ok());
+        assert!(check_component(".favorites").is_ok());
+    }
+
+    #[test]
+    fn check_component_rejects_parent_and_current_dirs() {
+        for component in ["..", ".", ""] {
+            let err = check_component(component).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{component:?}");
+        }
+    }
+
+    #[test]
+    fn check_component_rejects_absolute_paths() {
+        assert!(check_component("/etc").is_err());
+        assert!(check_component("\\windows").is_err());
+        assert!(check_component("C:\\windows").is_err());
+    }
+
+    #[test]
+    fn check_component_rejects_separators() {
+        assert!(check_component("bass/warm").is_err());
+        assert!(check_component("bass\\warm").is_err());
+        assert!(check_component("../warm").is_err());
+    }
+
+    #[test]
+    fn path_of_stays_under_the_root() {
+        let store = store();
+        assert_eq!(
+            store.path_of("bass/warm.preset").unwrap(),
+            Path::new("presets").join("bass").join("warm.preset")
+        );
+        for id in [
+            "../warm.preset",
+            "bass/../../warm.preset",
+            "/etc/passwd",
+            "bass//warm",
+        ] {
+            assert!(store.path_of(id).is_err(), "{id:?}");
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Keeps track of the parameters shown by cozy-ui widgets, so undo, MIDI learn and the integration can find and
+//! set them.
+//!
+//! Every widget registers its parameter each frame it's shown. The parameter id is the widget's id unless the
+//! widget is given another one with its `parameter_id` builder, which is how several widgets (say a knob and a
+//! number box) show the same parameter. Widgets that don't share the parameter id are treated as separate
+//! parameters, so in debug builds two widgets showing one parameter with different values are pointed out on screen.
+//!
+//! A value asked for with [`request_set`] is applied by the next widget showing the parameter that can take it,
+//! with that widget's `begin_set`/`end_set` bracket. Read-only, locked and disabled widgets can't, and a widget in
+//! the middle of a drag or scroll gesture waits for the gesture to end first
+
+use std::collections::{HashMap, HashSet};
+
+use egui::{Context, Id, Rect, Ui};
+
+const REGISTRY_ID: &str = "cozy_ui_parameter_registry";
+
+/// A parameter shown by a cozy-ui widget
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisteredParameter {
+    /// The parameter id. This is the widget's id unless the widget was given a separate parameter id
+    pub id: String,
+    /// The id of the widget that showed the parameter
+    pub widget: String,
+    /// The normalized value the parameter had when it was shown. Toggles register as `0.0` or `1.0`, selectors
+    /// spread their variants out from `0.0` for the first to `1.0` for the last
+    pub value: f32,
+    /// Where the widget showing the parameter was drawn
+    pub rect: Rect,
+}
+
+/// Every parameter shown by a cozy-ui widget, keyed by parameter id.
+///
+/// Widgets register their parameter each time they're shown, so the registry always reflects the last frame.
+/// Values can't be set directly since the parameters are only reachable through the widgets' get/set closures.
+/// Instead a value is requested with [`Registry::request_set`] and the widget applies it the next time it's shown,
+/// with the usual `begin_set`/`end_set` bracket
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    frame: u64,
+    current: Vec<RegisteredParameter>,
+    previous: Vec<RegisteredParameter>,
+    widgets: HashSet<String>,
+    pending: HashMap<String, f32>,
+}
+
+impl Registry {
+    /// The parameters shown in the last frame, in the order they were shown.
+    /// A parameter shown by several widgets is listed once per widget
+    #[must_use]
+    pub fn parameters(&self) -> &[RegisteredParameter] {
+        &self.previous
+    }
+
+    /// The value `parameter` had when it was last shown
+    #[must_use]
+    pub fn value(&self, parameter: &str) -> Option<f32> {
+        self.previous
+            .iter()
+            .find(|registered| registered.id == parameter)
+            .map(|registered| registered.value)
+    }
+
+    /// Asks the widgets showing `parameter` to set it to `value`
+    pub fn request_set(&mut self, parameter: &str, value: f32) {
+        self.pending.insert(parameter.to_owned(), value);
+    }
+
+    /// the widget that already showed `parameter` this frame with a different value than `value`, if any.
+    /// a widget that's being dragged or was set from the registry registers the value from before the change,
+    /// so only values that are the same as in the last frame count
+    fn conflicting_widget(&self, widget: &str, parameter: &str, value: f32) -> Option<String> {
+        let stable = |widget: &str, value: f32| {
+            self.previous
+                .iter()
+                .find(|registered| registered.id == parameter && registered.widget == widget)
+                .is_some_and(|previous| (previous.value - value).abs() <= f32::EPSILON)
+        };
+        let first = self
+            .current
+            .iter()
+            .find(|registered| registered.id == parameter && registered.widget != widget)?;
+        let conflict = (first.value - value).abs() > f32::EPSILON
+            && stable(&first.widget, first.value)
+            && stable(widget, value);
+        conflict.then(|| first.widget.clone())
+    }
+
+    fn begin_frame(&mut self, frame: u64) {
+        if self.frame != frame {
+            self.frame = frame;
+            self.previous = std::mem::take(&mut self.current);
+            self.widgets.clear();
+        }
+    }
+}
+
+/// Runs `f` with the registry stored in `ctx`
+pub fn with_registry<R>(ctx: &Context, f: impl FnOnce(&mut Registry) -> R) -> R {
+    let frame = ctx.frame_nr();
+    ctx.data_mut(|data| {
+        let registry: &mut Registry = data.get_temp_mut_or_default(Id::new(REGISTRY_ID));
+        registry.begin_frame(frame);
+        f(registry)
+    })
+}
+
+/// The parameters shown in the last frame, see [`Registry::parameters`]
+#[must_use]
+pub fn parameters(ctx: &Context) -> Vec<RegisteredParameter> {
+    with_registry(ctx, |registry| registry.parameters().to_vec())
+}
+
+/// See [`Registry::request_set`]
+pub fn request_set(ctx: &Context, parameter: &str, value: f32) {
+    with_registry(ctx, |registry| registry.request_set(parameter, value));
+    ctx.request_repaint();
+}
+
+/// Registers the widget `widget` showing `parameter`, returning the value the widget should set the parameter to, if any.
+/// A widget that isn't `editable` (read-only, locked or disabled) never gets one, the value is left for another widget.
+/// Two widgets sharing an id clash in egui's memory, and two widgets showing the same parameter with different values
+/// are most likely bound to different parameters by mistake, so in debug builds both are pointed out on screen
+pub(crate) fn register(
+    ui: &Ui,
+    widget: &str,
+    parameter: &str,
+    rect: Rect,
+    value: f32,
+    editable: bool,
+) -> Option<f32> {
+    let editable = editable && ui.is_enabled();
+    let (duplicate, conflict, pending) = with_registry(ui.ctx(), |registry| {
+        let duplicate = !registry.widgets.insert(widget.to_owned());
+        let conflict = registry.conflicting_widget(widget, parameter, value);
+        // widgets that can't be edited don't fire their callbacks, so the value waits for a widget that can
+        let pending = if editable {
+            registry.pending.remove(parameter)
+        } else {
+            None
+        };
+        registry.current.push(RegisteredParameter {
+            id: parameter.to_owned(),
+            widget: widget.to_owned(),
+            value: pending.unwrap_or(value),
+            rect,
+        });
+        (duplicate, conflict, pending)
+    });
+
+    if cfg!(debug_assertions) {
+        let error = if duplicate {
+            Some(format!("duplicate widget id {widget:?}"))
+        } else {
+            conflict.map(|other| {
+                format!(
+                    "{widget:?} and {other:?} show parameter {parameter:?} with different values"
+                )
+            })
+        };
+        if let Some(error) = error {
+            let painter = ui.ctx().debug_painter();
+            painter.rect_stroke(rect, 0.0, (1.0, ui.visuals().error_fg_color));
+            painter.error(rect.left_bottom(), error);
+        }
+    }
+
+    pending
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use egui::{Context, Id, Key, KeyboardShortcut, Modifiers};
+
+use crate::registry;
+
+const HISTORY_ID: &str = "cozy_ui_undo_history";
+
+pub const UNDO_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+pub const REDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+
+/// One completed gesture on a parameter
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edit {
+    /// The id of the edited parameter, see [`registry`]
+    pub parameter: String,
+    pub old: f32,
+    pub new: f32,
+}
+
+/// The undo and redo stacks for every parameter edited through cozy-ui widgets.
+///
+/// Widgets record into the history kept in the [`Context`] (see [`with_history`]). Undoing and redoing through
+/// [`undo`] and [`redo`] hands the value to the [`registry`], which has the widgets showing the parameter set it
+#[derive(Clone, Debug)]
+pub struct UndoHistory {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    gestures: HashMap<String, f32>,
+    limit: usize,
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            gestures: HashMap::new(),
+            limit: 100,
+        }
+    }
+}
+
+impl UndoHistory {
+    /// Starts a gesture on `parameter`, remembering `value` as the value to go back to
+    pub fn begin(&mut self, parameter: &str, value: f32) {
+        self.gestures.entry(parameter.to_owned()).or_insert(value);
+    }
+
+    /// Finishes a gesture on `parameter`, recording an edit if the value actually changed
+    pub fn end(&mut self, parameter: &str, value: f32) {
+        if let Some(old) = self.gestures.remove(parameter) {
+            if (old - value).abs() > f32::EPSILON {
+                self.push(Edit {
+                    parameter: parameter.to_owned(),
+                    old,
+                    new: value,
+                });
+            }
+        }
+    }
+
+    /// Records a finished edit. This clears the redo stack
+    pub fn push(&mut self, edit: Edit) {
+        self.redo.clear();
+        self.undo.push(edit);
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Moves the latest edit to the redo stack, returning it
+    pub fn undo(&mut self) -> Option<Edit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Moves the latest undone edit back to the undo stack, returning it
+    pub fn redo(&mut self) -> Option<Edit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        Some(edit)
+    }
+
+    #[must_use]
+    pub const fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    #[must_use]
+    pub const fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Sets how many edits are kept. The oldest are dropped first
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        let excess = self.undo.len().saturating_sub(limit);
+        self.undo.drain(..excess);
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.gestures.clear();
+    }
+}
+
+/// Runs `f` with the undo history stored in `ctx`
+pub fn with_history<R>(ctx: &Context, f: impl FnOnce(&mut UndoHistory) -> R) -> R {
+    ctx.data_mut(|data| f(data.get_temp_mut_or_default(Id::new(HISTORY_ID))))
+}
+
+/// Reverts the latest edit, setting its parameter back through the [`registry`]
+pub fn undo(ctx: &Context) -> Option<Edit> {
+    let edit = with_history(ctx, UndoHistory::undo)?;
+    registry::request_set(ctx, &edit.parameter, edit.old);
+    Some(edit)
+}
+
+/// Reapplies the latest undone edit through the [`registry`]
+pub fn redo(ctx: &Context) -> Option<Edit> {
+    let edit = with_history(ctx, UndoHistory::redo)?;
+    registry::request_set(ctx, &edit.parameter, edit.new);
+    Some(edit)
+}
+
+/// Handles [`UNDO_SHORTCUT`] and [`REDO_SHORTCUT`], unless a text field has keyboard focus.
+/// [`crate::setup`] runs this at the start of every frame
+pub fn handle_shortcuts(ctx: &Context) {
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+
+    // redo first, shortcuts ignore extra shift modifiers so ctrl+z would also match ctrl+shift+z
+    if ctx.input_mut(|input| input.consume_shortcut(&REDO_SHORTCUT)) {
+        redo(ctx);
+    } else if ctx.input_mut(|input| input.consume_shortcut(&UNDO_SHORTCUT)) {
+        undo(ctx);
+    }
+}
+
+pub(crate) fn begin_edit(ctx: &Context, parameter: &str, value: f32) {
+    with_history(ctx, |history| history.begin(parameter, value));
+}
+
+pub(crate) fn end_edit(ctx: &Context, parameter: &str, value: f32) {
+    with_history(ctx, |history| history.end(parameter, value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(history: &mut UndoHistory, parameter: &str, old: f32, new: f32) {
+        history.begin(parameter, old);
+        history.end(parameter, new);
+    }
+
+    #[test]
+    fn gestures_record_one_edit() {
+        let mut history = UndoHistory::default();
+        history.begin("gain", 0.2);
+        // a gesture that's begun again keeps the value from before the first begin
+        history.begin("gain", 0.4);
+        history.end("gain", 0.6);
+
+        assert_eq!(
+            history.undo(),
+            Some(Edit {
+                parameter: "gain".to_owned(),
+                old: 0.2,
+                new: 0.6,
+            })
+        );
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn unchanged_gestures_are_not_recorded() {
+        let mut history = UndoHistory::default();
+        edit(&mut history, "gain", 0.5, 0.5);
+        history.end("cutoff", 0.3);
+
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undo_then_redo_restores_the_edit() {
+        let mut history = UndoHistory::default();
+        edit(&mut history, "gain", 0.2, 0.6);
+
+        let undone = history.undo().unwrap();
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+
+        assert_eq!(history.redo(), Some(undone));
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn new_edit_clears_the_redo_stack() {
+        let mut history = UndoHistory::default();
+        edit(&mut history, "gain", 0.2, 0.6);
+        edit(&mut history, "cutoff", 0.1, 0.9);
+        history.undo();
+        history.redo();
+        history.undo();
+        assert!(history.can_redo());
+
+        edit(&mut history, "gain", 0.6, 0.4);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo().map(|edit| edit.new), Some(0.4));
+        assert_eq!(history.undo().map(|edit| edit.new), Some(0.6));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn limit_drops_the_oldest_edits() {
+        let mut history = UndoHistory::default();
+        for step in 0..5u8 {
+            edit(&mut history, "gain", 0.0, f32::from(step + 1) / 10.0);
+        }
+        history.set_limit(2);
+
+        assert_eq!(history.undo().map(|edit| edit.new), Some(0.5));
+        assert_eq!(history.undo().map(|edit| edit.new), Some(0.4));
+        assert_eq!(history.undo(), None);
+    }
+}
@@ -1,6 +1,10 @@
 use std::f32::consts::PI;
 
-use egui::{epaint::CubicBezierShape, Color32, Painter, Pos2, Shape, Stroke, Vec2};
+use egui::{
+    emath::lerp,
+    epaint::{CubicBezierShape, Mesh},
+    Color32, Painter, Pos2, Shape, Stroke, Vec2,
+};
 
 pub mod get_set;
 
@@ -88,3 +92,35 @@ pub fn generate_arc(
         }),
     );
 }
+
+/// fills the circle sector between `start_angle` and `end_angle`. like [`generate_arc`], angles are in radians
+/// and go counterclockwise from the right
+pub fn generate_sector(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    color: Color32,
+) {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let steps = ((end_angle - start_angle).abs() / (PI / 32.0))
+        .ceil()
+        .max(1.0) as u32;
+
+    let mut mesh = Mesh::default();
+    mesh.colored_vertex(center, color);
+    for step in 0..=steps {
+        #[allow(clippy::cast_precision_loss)]
+        let angle = lerp(start_angle..=end_angle, step as f32 / steps as f32);
+        mesh.colored_vertex(
+            center + radius * Vec2::new(angle.cos(), -angle.sin()),
+            color,
+        );
+    }
+    for step in 1..=steps {
+        mesh.add_triangle(0, step, step + 1);
+    }
+
+    painter.add(Shape::mesh(mesh));
+}
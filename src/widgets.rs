@@ -1,12 +1,87 @@
+//! The cozy-ui widgets. Each one edits its parameter through a get/set closure, calling `begin_set` and `end_set`
+//! around every gesture so the host can record it as one change.
+//!
+//! Widgets can be made `read_only` for values the user shouldn't change from the editor. They still show their
+//! value, tooltips and readout, but ignore the pointer, the keyboard, the scroll wheel and values requested through
+//! the [`crate::registry`]
+
+pub mod ab_comparison;
 pub mod button;
+pub mod encoder;
+pub mod group;
 pub mod knob;
+pub mod number_box;
+pub mod piano;
+pub mod preset_browser;
+pub mod selector;
 pub mod slider;
+pub mod xy_pad;
 
-pub use button::toggle;
+pub use ab_comparison::ab_comparison;
+pub use button::{checkbox, momentary, radio_group, toggle, trigger};
+pub use encoder::encoder;
+pub use group::group;
 pub use knob::Knob;
+pub use number_box::number_box;
+pub use piano::piano;
+pub use preset_browser::preset_browser;
+pub use selector::{dropdown, segmented};
 pub use slider::slider;
+pub use xy_pad::xy_pad;
+
+use std::{collections::HashMap, time::Duration};
+
+use egui::{
+    vec2, Align2, Area, Button, Color32, Context, Frame, Id, Order, Pos2, Rect, Response, Stroke,
+    Ui, Vec2, ViewportCommand,
+};
+
+use crate::{
+    colors::{
+        AUTOMATION_OVERRIDE_COL32, AUTOMATION_READ_COL32, AUTOMATION_WRITE_COL32, HIGHLIGHT_COL32,
+    },
+    interaction::{interaction_settings, ResetGesture},
+    midi, undo,
+    util::get_set::Operation,
+};
+
+/// how long the scroll wheel has to be still before a scroll gesture ends
+const SCROLL_GESTURE_TIMEOUT: f64 = 0.3;
+
+/// how long the pointer has to rest on a widget before its value is shown
+const READOUT_HOVER_DELAY: f64 = 0.5;
+
+const CLIPBOARD_ID: &str = "cozy_ui_value_clipboard";
+
+const SCROLL_GESTURES_ID: &str = "cozy_ui_scroll_gestures";
+
+type Formatter<'a> = Box<dyn Fn(f32) -> String + 'a>;
+
+/// What the host says it's doing with a parameter's automation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AutomationState {
+    #[default]
+    None,
+    /// The parameter follows recorded automation
+    Automated,
+    /// The host is recording the parameter's automation
+    Writing,
+    /// The parameter has automation but the user moved it away from it
+    Overridden,
+}
 
-use crate::util::get_set::Operation;
+impl AutomationState {
+    /// The color the state is shown in, if it's shown at all
+    #[must_use]
+    pub const fn color(self) -> Option<Color32> {
+        match self {
+            Self::None => None,
+            Self::Automated => Some(AUTOMATION_READ_COL32),
+            Self::Writing => Some(AUTOMATION_WRITE_COL32),
+            Self::Overridden => Some(AUTOMATION_OVERRIDE_COL32),
+        }
+    }
+}
 
 fn get<T, GetSet>(operator: &mut GetSet) -> T
 where
@@ -21,3 +96,422 @@ where
 {
     operator(Operation::Set(value));
 }
+
+/// sets the parameter to the value the [`crate::registry`] asked for, if it asked for one, with the usual
+/// `begin_set`/`end_set` bracket. returns the value that was set
+fn apply_requested<T: Copy>(
+    response: &mut Response,
+    value: &mut impl FnMut(Operation<T>) -> T,
+    requested: Option<T>,
+    begin_set: &impl Fn(),
+    end_set: &impl Fn(),
+) -> Option<T> {
+    let requested = requested?;
+    begin_set();
+    set(value, requested);
+    end_set();
+    response.mark_changed();
+    Some(requested)
+}
+
+/// the normalized value of `variant`, `0.0` for the first variant and `1.0` for the last.
+/// selectors register and record their undo entries with it
+#[allow(clippy::cast_precision_loss)]
+fn variant_value<T: PartialEq>(variants: &[(T, &str)], variant: &T) -> f32 {
+    let index = variants
+        .iter()
+        .position(|(other, _)| other == variant)
+        .unwrap_or_default();
+    if variants.len() > 1 {
+        index as f32 / (variants.len() - 1) as f32
+    } else {
+        0.0
+    }
+}
+
+/// the variant a normalized value from [`variant_value`] stands for
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn variant_at<T: Copy>(variants: &[(T, &str)], value: f32) -> Option<T> {
+    let last = variants.len().checked_sub(1)?;
+    let index = (value.clamp(0.0, 1.0) * last as f32).round() as usize;
+    Some(variants[index.min(last)].0)
+}
+
+/// whether the fine adjustment modifier from the [`crate::interaction::InteractionSettings`] is held
+fn fine_adjustment(ui: &Ui) -> bool {
+    let fine_modifier = interaction_settings(ui.ctx()).fine_modifier;
+    ui.input(|input| input.modifiers.contains(fine_modifier))
+}
+
+/// how far a drag of `delta` points moves a normalized value, see [`crate::interaction::InteractionSettings::pixels_per_range`]
+fn drag_distance(ui: &Ui, delta: f32, granular: bool) -> f32 {
+    let settings = interaction_settings(ui.ctx());
+    let fine_factor = if granular {
+        settings.fine_factor.max(1.0)
+    } else {
+        1.0
+    };
+    delta / (settings.pixels_per_range.max(1.0) * fine_factor)
+}
+
+/// how far a scroll of `delta` points moves a normalized value, see [`crate::interaction::InteractionSettings::scroll_step`]
+fn scroll_distance(ui: &Ui, delta: f32, granular: bool) -> f32 {
+    let settings = interaction_settings(ui.ctx());
+    let fine_factor = if granular {
+        settings.fine_factor.max(1.0)
+    } else {
+        1.0
+    };
+    let lines = delta / ui.ctx().options(|options| options.line_scroll_speed);
+    lines * settings.scroll_step / fine_factor
+}
+
+/// moves a normalized value by a drag of `delta` points, see [`drag_distance`]
+fn drag_normalized(ui: &Ui, value: f32, delta: f32, granular: bool) -> f32 {
+    (value + drag_distance(ui, delta, granular)).clamp(0.0, 1.0)
+}
+
+/// moves a normalized value by a scroll of `delta` points, see [`scroll_distance`]
+fn scroll_normalized(ui: &Ui, value: f32, delta: f32, granular: bool) -> f32 {
+    (value + scroll_distance(ui, delta, granular)).clamp(0.0, 1.0)
+}
+
+/// whether the widget was reset to its default with the [`ResetGesture`] from the [`crate::interaction::InteractionSettings`]
+fn reset_requested(ui: &Ui, response: &Response) -> bool {
+    match interaction_settings(ui.ctx()).reset_gesture {
+        ResetGesture::DoubleClick => response.double_clicked(),
+        ResetGesture::CommandClick => {
+            response.clicked() && ui.input(|input| input.modifiers.command)
+        }
+    }
+}
+
+/// a burst of scroll wheel events on one widget, see [`begin_scroll_gesture`]
+#[derive(Clone, Debug)]
+struct ScrollGesture {
+    /// when the wheel last moved
+    last: f64,
+    /// the frame the widget was last shown in
+    frame: u64,
+    parameter: String,
+    /// the value the last scroll left the parameter at, see [`scrolled_to`]
+    value: Option<f32>,
+    /// ended by [`end_idle_scroll_gestures`], waiting for the widget to call its `end_set`
+    ended: bool,
+}
+
+fn with_scroll_gestures<R>(
+    ctx: &Context,
+    f: impl FnOnce(&mut HashMap<Id, ScrollGesture>) -> R,
+) -> R {
+    ctx.data_mut(|data| f(data.get_temp_mut_or_default(Id::new(SCROLL_GESTURES_ID))))
+}
+
+/// scroll wheels send a burst of events, one per frame. this keeps track of the whole burst so it can be
+/// bracketed with a single `begin_set`/`end_set` pair. returns `true` if this scroll started a new gesture
+fn begin_scroll_gesture(ui: &Ui, gesture_id: Id, parameter: &str) -> bool {
+    let now = ui.input(|input| input.time);
+    let frame = ui.ctx().frame_nr();
+    let started = with_scroll_gestures(ui.ctx(), |gestures| {
+        gestures
+            .insert(
+                gesture_id,
+                ScrollGesture {
+                    last: now,
+                    frame,
+                    parameter: parameter.to_owned(),
+                    value: gestures.get(&gesture_id).and_then(|gesture| gesture.value),
+                    ended: false,
+                },
+            )
+            .is_none()
+    });
+    ui.ctx()
+        .request_repaint_after(Duration::from_secs_f64(SCROLL_GESTURE_TIMEOUT));
+    started
+}
+
+/// remembers the value a scroll left the parameter at, so the undo entry can be ended with it even if the widget
+/// isn't shown again
+fn scrolled_to(ui: &Ui, gesture_id: Id, value: f32) {
+    with_scroll_gestures(ui.ctx(), |gestures| {
+        if let Some(gesture) = gestures.get_mut(&gesture_id) {
+            gesture.value = Some(value);
+        }
+    });
+}
+
+/// returns `true` once the scroll gesture is over, either because the wheel stopped or because something
+/// else (like a drag) `interrupted` it
+fn end_scroll_gesture(ui: &Ui, gesture_id: Id, interrupted: bool) -> bool {
+    let now = ui.input(|input| input.time);
+    let frame = ui.ctx().frame_nr();
+    with_scroll_gestures(ui.ctx(), |gestures| {
+        let Some(gesture) = gestures.get_mut(&gesture_id) else {
+            return false;
+        };
+        gesture.frame = frame;
+        let ended = interrupted || gesture.ended || now - gesture.last >= SCROLL_GESTURE_TIMEOUT;
+        if ended {
+            gestures.remove(&gesture_id);
+        }
+        ended
+    })
+}
+
+/// ends the undo entries of the scroll gestures the wheel stopped on, so they're recorded even if the widget isn't
+/// shown again, e.g. because its section was collapsed. the widget calls its `end_set` the next time it's shown,
+/// unless [`take_ended_scroll_gestures`] got to it first. [`crate::setup`] runs this at the start of every frame
+pub(crate) fn end_idle_scroll_gestures(ctx: &Context) {
+    let now = ctx.input(|input| input.time);
+    let idle: Vec<(String, Option<f32>)> = with_scroll_gestures(ctx, |gestures| {
+        gestures
+            .values_mut()
+            .filter(|gesture| !gesture.ended && now - gesture.last >= SCROLL_GESTURE_TIMEOUT)
+            .map(|gesture| {
+                gesture.ended = true;
+                (gesture.parameter.clone(), gesture.value)
+            })
+            .collect()
+    });
+
+    for (parameter, value) in idle {
+        if let Some(value) = value {
+            undo::end_edit(ctx, &parameter, value);
+        }
+    }
+}
+
+/// The parameters whose scroll wheel gestures ended while their widget wasn't shown, e.g. because its tab was closed.
+///
+/// Whoever takes them has to end the host's gesture on each parameter, like `end_set` would. Otherwise each
+/// widget calls its `end_set` the next time it's shown
+#[must_use]
+pub fn take_ended_scroll_gestures(ctx: &Context) -> Vec<String> {
+    let frame = ctx.frame_nr();
+    with_scroll_gestures(ctx, |gestures| {
+        let mut ended = Vec::new();
+        // widgets that were shown last frame end their own gestures when they're shown again
+        gestures.retain(|_, gesture| {
+            let take = gesture.ended && gesture.frame + 1 < frame;
+            if take {
+                ended.push(gesture.parameter.clone());
+            }
+            !take
+        });
+        ended
+    })
+}
+
+/// marks a drag gesture as open, so it's still closed if the widget stops being interactive in the middle of it,
+/// see [`end_drag_gesture`]
+fn begin_drag_gesture(ui: &Ui, gesture_id: Id) {
+    ui.memory_mut(|mem| mem.data.insert_temp(gesture_id, true));
+}
+
+/// whether a drag gesture started by [`begin_drag_gesture`] hasn't been closed yet
+fn drag_gesture_open(ui: &Ui, gesture_id: Id) -> bool {
+    ui.memory(|mem| mem.data.get_temp(gesture_id).unwrap_or(false))
+}
+
+/// returns `true` once an open drag gesture is over. that's usually when the drag stops, but a widget that turns
+/// disabled, locked or read-only mid-drag only senses hovers from then on and never sees `drag_stopped`
+fn end_drag_gesture(ui: &Ui, gesture_id: Id, dragged: bool) -> bool {
+    !dragged
+        && ui
+            .memory_mut(|mem| mem.data.remove_temp::<bool>(gesture_id))
+            .unwrap_or(false)
+}
+
+/// starts a drag that keeps going when the pointer would hit the edge of the screen, by warping the pointer back
+/// to where the drag started every frame. this needs the integration to handle `ViewportCommand::CursorPosition`,
+/// see [`knob::Knob::infinite_drag`]
+fn begin_warped_drag(ui: &Ui, origin_id: Id) {
+    if let Some(origin) = ui.input(|input| input.pointer.latest_pos()) {
+        ui.memory_mut(|mem| mem.data.insert_temp(origin_id, origin));
+    }
+}
+
+/// how far the pointer moved since it was last warped back, see [`begin_warped_drag`]
+fn warped_drag_delta(ui: &Ui, origin_id: Id) -> Vec2 {
+    let Some(origin) = ui.memory(|mem| mem.data.get_temp::<Pos2>(origin_id)) else {
+        return ui.input(|input| input.pointer.delta());
+    };
+
+    // some platforms don't report the warp as a move, so a pointer that didn't move is still where it was warped to
+    let delta = ui.input(|input| {
+        input
+            .pointer
+            .latest_pos()
+            .filter(|_| input.pointer.delta() != Vec2::ZERO)
+            .map_or(Vec2::ZERO, |position| position - origin)
+    });
+    if delta != Vec2::ZERO {
+        ui.ctx()
+            .send_viewport_cmd(ViewportCommand::CursorPosition(origin));
+    }
+    delta
+}
+
+/// puts the pointer back where the drag started, not on the widget's center
+fn end_warped_drag(ui: &Ui, origin_id: Id) {
+    if let Some(origin) = ui.memory_mut(|mem| mem.data.remove_temp::<Pos2>(origin_id)) {
+        ui.ctx()
+            .send_viewport_cmd(ViewportCommand::CursorPosition(origin));
+    }
+}
+
+/// whether the widget is in the middle of a drag or scroll gesture, which values from the registry have to wait
+/// for, so the host doesn't get a `begin_set`/`end_set` pair inside the gesture's own
+fn gesture_open(ui: &Ui, drag_gesture_id: Id, scroll_gesture_id: Id) -> bool {
+    drag_gesture_open(ui, drag_gesture_id)
+        || with_scroll_gestures(ui.ctx(), |gestures| {
+            gestures.contains_key(&scroll_gesture_id)
+        })
+}
+
+/// whether a scroll gesture started by [`begin_scroll_gesture`] is still going
+fn scroll_gesture_active(ui: &Ui, gesture_id: Id) -> bool {
+    with_scroll_gestures(ui.ctx(), |gestures| {
+        gestures
+            .get(&gesture_id)
+            .is_some_and(|gesture| !gesture.ended)
+    })
+}
+
+/// formats a value for display, with two decimals unless there's a `formatter`
+fn format_value(formatter: Option<&Formatter>, value: f32) -> String {
+    formatter.map_or_else(|| format!("{value:.2}"), |formatter| formatter(value))
+}
+
+/// shows `text` in a bubble above the widget while it's being changed (`active`) or once the pointer has
+/// rested on it for a moment. the bubble stays put, so it's still readable while the cursor is hidden
+fn value_readout(
+    ui: &Ui,
+    response: &Response,
+    readout_id: Id,
+    active: bool,
+    text: impl FnOnce() -> String,
+) {
+    let now = ui.input(|input| input.time);
+    let hover_start_id = readout_id.with("hover_start");
+    let hovered_for = if response.hovered() {
+        let hover_start =
+            ui.memory_mut(|mem| *mem.data.get_temp_mut_or_insert_with(hover_start_id, || now));
+        now - hover_start
+    } else {
+        ui.memory_mut(|mem| mem.data.remove::<f64>(hover_start_id));
+        0.0
+    };
+
+    if !active && hovered_for < READOUT_HOVER_DELAY {
+        if response.hovered() {
+            #[allow(clippy::cast_possible_truncation)]
+            ui.ctx()
+                .request_repaint_after_secs((READOUT_HOVER_DELAY - hovered_for) as f32);
+        }
+        return;
+    }
+
+    Area::new(readout_id)
+        .order(Order::Tooltip)
+        .fixed_pos(response.rect.center_top() - vec2(0.0, 4.0))
+        .pivot(Align2::CENTER_BOTTOM)
+        .interactable(false)
+        .show(ui.ctx(), |ui| {
+            Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(text());
+            });
+        });
+}
+
+/// the right click menu shared by the parameter widgets. returns the value the parameter should be set to, if any
+fn parameter_menu(
+    response: &Response,
+    parameter: &str,
+    value: f32,
+    default: Option<f32>,
+) -> Option<f32> {
+    let mut new_value = None;
+
+    response.context_menu(|ui| {
+        let ctx = ui.ctx().clone();
+        let (learning, mapping) = midi::with_midi_learn(&ctx, |midi_learn| {
+            (
+                midi_learn.learning() == Some(parameter),
+                midi_learn.mapping(parameter),
+            )
+        });
+
+        if learning {
+            if ui.button("Cancel MIDI Learn").clicked() {
+                midi::with_midi_learn(&ctx, midi::MidiLearn::cancel_learn);
+                ui.close_menu();
+            }
+        } else if ui.button("MIDI Learn").clicked() {
+            midi::with_midi_learn(&ctx, |midi_learn| midi_learn.learn(parameter));
+            ui.close_menu();
+        }
+        let clear_text = mapping.map_or_else(
+            || "Clear MIDI mapping".to_owned(),
+            |cc| format!("Clear MIDI mapping ({cc})"),
+        );
+        if ui
+            .add_enabled(mapping.is_some(), Button::new(clear_text))
+            .clicked()
+        {
+            midi::with_midi_learn(&ctx, |midi_learn| midi_learn.clear_mapping(parameter));
+            ui.close_menu();
+        }
+
+        ui.separator();
+
+        if ui
+            .add_enabled(default.is_some(), Button::new("Reset to default"))
+            .clicked()
+        {
+            new_value = default;
+            ui.close_menu();
+        }
+        if ui.button("Copy value").clicked() {
+            ctx.data_mut(|data| data.insert_temp(Id::new(CLIPBOARD_ID), value));
+            ctx.copy_text(value.to_string());
+            ui.close_menu();
+        }
+        let clipboard = ctx.data(|data| data.get_temp::<f32>(Id::new(CLIPBOARD_ID)));
+        if ui
+            .add_enabled(clipboard.is_some(), Button::new("Paste value"))
+            .clicked()
+        {
+            new_value = clipboard;
+            ui.close_menu();
+        }
+    });
+
+    new_value
+}
+
+/// pulses an outline around `rect` while `parameter` is waiting for a CC in MIDI learn mode
+fn paint_learn(ui: &Ui, rect: Rect, rounding: f32, animation_id: Id, parameter: &str) {
+    let learning = midi::is_learning(ui.ctx(), parameter);
+    let animated = ui.ctx().animate_bool(animation_id, learning);
+    if animated <= 0.0 {
+        return;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let pulse = (ui.input(|input| input.time) * 6.0).sin() as f32;
+    let alpha = animated * pulse.mul_add(0.35, 0.65);
+    ui.painter().rect_stroke(
+        rect.expand(2.0),
+        rounding + 2.0,
+        Stroke::new(2.0, HIGHLIGHT_COL32.gamma_multiply(alpha)),
+    );
+    if learning {
+        ui.ctx().request_repaint();
+    }
+}
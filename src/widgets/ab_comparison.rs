@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use egui::{Response, Sense, Ui, Widget, WidgetInfo, WidgetText, WidgetType};
+
+use crate::util::get_set::Operation;
+
+use super::{
+    button::{layout, paint},
+    get, set,
+};
+
+pub const fn ab_comparison<'a, 'p>(
+    id: &'a str,
+    parameters: &'a mut [SnapshotParameter<'p>],
+) -> AbComparison<'a, 'p> {
+    AbComparison::new(id, parameters)
+}
+
+/// One of the two snapshot slots
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Slot {
+    #[default]
+    A,
+    B,
+}
+
+impl Slot {
+    #[must_use]
+    pub const fn other(self) -> Self {
+        match self {
+            Self::A => Self::B,
+            Self::B => Self::A,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::B => "B",
+        }
+    }
+}
+
+/// A parameter the A/B snapshots capture and restore through its get/set closure
+pub struct SnapshotParameter<'a> {
+    id: &'a str,
+    value: Box<dyn FnMut(Operation<f32>) -> f32 + 'a>,
+    begin_set: Box<dyn Fn() + 'a>,
+    end_set: Box<dyn Fn() + 'a>,
+}
+
+impl<'a> SnapshotParameter<'a> {
+    /// `id` has to stay the same between frames, it's what the snapshots are keyed by
+    pub fn new(
+        id: &'a str,
+        value: impl FnMut(Operation<f32>) -> f32 + 'a,
+        begin_set: impl Fn() + 'a,
+        end_set: impl Fn() + 'a,
+    ) -> Self {
+        Self {
+            id,
+            value: Box::new(value),
+            begin_set: Box::new(begin_set),
+            end_set: Box::new(end_set),
+        }
+    }
+
+    fn restore(&mut self, value: f32) {
+        if (get(&mut self.value) - value).abs() > f32::EPSILON {
+            (self.begin_set)();
+            set(&mut self.value, value);
+            (self.end_set)();
+        }
+    }
+}
+
+/// The parameter values of both slots, keyed by parameter id
+#[derive(Clone, Debug, Default)]
+pub struct Snapshots {
+    active: Slot,
+    a: HashMap<String, f32>,
+    b: HashMap<String, f32>,
+}
+
+impl Snapshots {
+    #[must_use]
+    pub const fn active(&self) -> Slot {
+        self.active
+    }
+
+    const fn slot_mut(&mut self, slot: Slot) -> &mut HashMap<String, f32> {
+        match slot {
+            Slot::A => &mut self.a,
+            Slot::B => &mut self.b,
+        }
+    }
+
+    /// Stores the current parameter values in the active slot
+    pub fn capture(&mut self, parameters: &mut [SnapshotParameter]) {
+        let slot = self.slot_mut(self.active);
+        for parameter in parameters {
+            slot.insert(parameter.id.to_owned(), get(&mut parameter.value));
+        }
+    }
+
+    /// Captures the active slot and restores the other one. The first switch to a slot that was never
+    /// captured keeps the current values, so both slots start out the same
+    pub fn switch(&mut self, parameters: &mut [SnapshotParameter]) {
+        self.capture(parameters);
+        self.active = self.active.other();
+
+        if self.slot_mut(self.active).is_empty() {
+            self.capture(parameters);
+            return;
+        }
+
+        let slot = self.slot_mut(self.active).clone();
+        for parameter in parameters {
+            if let Some(value) = slot.get(parameter.id) {
+                parameter.restore(*value);
+            }
+        }
+    }
+
+    /// Overwrites the inactive slot with the current parameter values
+    pub fn copy_to_other(&mut self, parameters: &mut [SnapshotParameter]) {
+        self.capture(parameters);
+        let captured = self.slot_mut(self.active).clone();
+        *self.slot_mut(self.active.other()) = captured;
+    }
+}
+
+/// A header with an A and a B button to flip between two sets of parameter values, and a button
+/// to copy the active set over the other one
+#[must_use]
+pub struct AbComparison<'a, 'p> {
+    id: &'a str,
+    parameters: &'a mut [SnapshotParameter<'p>],
+    small: bool,
+}
+
+impl<'a, 'p> AbComparison<'a, 'p> {
+    pub const fn new(id: &'a str, parameters: &'a mut [SnapshotParameter<'p>]) -> Self {
+        Self {
+            id,
+            parameters,
+            small: false,
+        }
+    }
+
+    pub const fn small(mut self) -> Self {
+        self.small = true;
+        self
+    }
+
+    fn button(&self, ui: &mut Ui, text: &str, lit: bool) -> Response {
+        let id = self.id;
+        let (galley, desired_size, button_padding) = layout(ui, WidgetText::from(text), self.small);
+        let (rect, response) = ui.allocate_at_least(desired_size, Sense::click());
+        response.widget_info(|| WidgetInfo::selected(WidgetType::Button, true, lit, text));
+
+        let animated_value = ui
+            .ctx()
+            .animate_bool(format!("ab_{id}_{text}_light").into(), lit);
+        paint(ui, rect, &response, galley, button_padding, animated_value);
+
+        response
+    }
+}
+
+impl Widget for AbComparison<'_, '_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let snapshots_id = format!("ab_{id}_snapshots").into();
+        let mut snapshots: Snapshots =
+            ui.memory(|mem| mem.data.get_temp(snapshots_id).unwrap_or_default());
+        let active = snapshots.active();
+
+        let mut response = ui
+            .horizontal(|ui| {
+                let mut response = self.button(ui, Slot::A.name(), active == Slot::A);
+                let b = self.button(ui, Slot::B.name(), active == Slot::B);
+                if (response.clicked() && active == Slot::B) || (b.clicked() && active == Slot::A) {
+                    snapshots.switch(self.parameters);
+                }
+                response |= b;
+
+                let (from, to) = (active.name(), active.other().name());
+                let copy = self
+                    .button(ui, &format!("{from}→{to}"), false)
+                    .on_hover_text(format!("copy {from} to {to}"));
+                if copy.clicked() {
+                    snapshots.copy_to_other(self.parameters);
+                }
+                response | copy
+            })
+            .inner;
+
+        if snapshots.active() != active {
+            response.mark_changed();
+        }
+
+        ui.memory_mut(|mem| mem.data.insert_temp(snapshots_id, snapshots));
+
+        response
+    }
+}
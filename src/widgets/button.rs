@@ -1,16 +1,22 @@
 use colorgrad::{BasisGradient, Color, Gradient, GradientBuilder};
+use std::{collections::HashMap, sync::Arc};
+
 use egui::{
-    pos2, vec2, Color32, NumExt, Rect, Rounding, Sense, Stroke, TextStyle, Ui, Vec2, Widget,
-    WidgetInfo, WidgetText, WidgetType,
+    pos2, vec2, Align, Color32, Context, Galley, Id, InnerResponse, Key, Layout, NumExt, Painter,
+    Rect, Response, Rounding, Sense, Stroke, TextStyle, Ui, Vec2, Widget, WidgetInfo, WidgetText,
+    WidgetType,
 };
 use once_cell::sync::Lazy;
 
 use crate::{
     colors::{BACKGROUND, HIGHLIGHT},
+    registry, undo,
     util::get_set::Operation,
 };
 
-use super::{get, set};
+use super::{apply_requested, get, paint_learn, parameter_menu, set, variant_at, variant_value};
+
+const MOMENTARY_ID: &str = "cozy_ui_momentary_buttons";
 
 static LIGHT_GRADIENT: Lazy<BasisGradient> = Lazy::new(|| {
     GradientBuilder::new()
@@ -20,6 +26,29 @@ static LIGHT_GRADIENT: Lazy<BasisGradient> = Lazy::new(|| {
         .unwrap()
 });
 
+/// the color of the little indicator light, `animated_value` being how far it's turned on
+pub(super) fn light_color(animated_value: f32) -> Color32 {
+    let color = LIGHT_GRADIENT.at(animated_value).to_rgba8();
+    Color32::from_rgb(color[0], color[1], color[2])
+}
+
+/// paints the indicator light along the left edge of a button shaped `rect`
+pub(super) fn paint_light(
+    painter: &Painter,
+    rect: Rect,
+    button_padding: Vec2,
+    animated_value: f32,
+) {
+    let light_rect_pos = pos2(rect.min.x + button_padding.x + 3.0, rect.center().y);
+    let light_rect = Rect::from_center_size(light_rect_pos, vec2(4.0, rect.height() - 4.0));
+    painter.rect(
+        light_rect,
+        Rounding::same(10.0),
+        light_color(animated_value),
+        Stroke::NONE,
+    );
+}
+
 pub fn toggle<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>(
     id: &str,
     text: impl Into<WidgetText>,
@@ -33,10 +62,13 @@ pub fn toggle<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>(
 #[must_use]
 pub struct Toggle<'a, GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()> {
     id: &'a str,
+    parameter: Option<&'a str>,
     description: Option<WidgetText>,
     value: GetSet,
     small: bool,
     text: WidgetText,
+    default: Option<bool>,
+    read_only: bool,
     begin_set: Start,
     end_set: End,
 }
@@ -53,10 +85,13 @@ impl<'a, GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>
     ) -> Self {
         Self {
             id,
+            parameter: None,
             description: None,
             value,
             small: false,
             text: text.into(),
+            default: None,
+            read_only: false,
             begin_set,
             end_set,
         }
@@ -67,10 +102,28 @@ impl<'a, GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>
         self
     }
 
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
     pub const fn small(mut self) -> Self {
         self.small = true;
         self
     }
+
+    /// Sets the value "Reset to default" in the context menu goes back to
+    pub const fn default_value(mut self, default: bool) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
 }
 
 impl<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()> Widget
@@ -78,82 +131,772 @@ impl<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()> Widget
 {
     fn ui(mut self, ui: &mut Ui) -> egui::Response {
         let id = self.id;
-        let mut button_padding = ui.spacing().button_padding;
-        if self.small {
-            button_padding.y = 0.0;
+        let parameter = self.parameter.unwrap_or(id);
+        let (galley, desired_size, button_padding) = layout(ui, self.text, self.small);
+
+        let sense = if self.read_only {
+            Sense::hover()
+        } else {
+            Sense::click()
+        };
+        let (rect, mut response) = ui.allocate_at_least(desired_size, sense);
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
+        }
+
+        let value = f32::from(u8::from(get(&mut self.value)));
+        let requested = registry::register(ui, id, parameter, rect, value, !self.read_only);
+        apply_requested(
+            &mut response,
+            &mut self.value,
+            requested.map(|value| value > 0.5),
+            &self.begin_set,
+            &self.end_set,
+        );
+
+        let mut new_value = get(&mut self.value);
+        let menu_value = parameter_menu(
+            &response,
+            parameter,
+            f32::from(u8::from(new_value)),
+            self.default.map(|default| f32::from(u8::from(default))),
+        )
+        .map(|value| value > 0.5);
+        if let Some(value) = menu_value.or_else(|| response.clicked().then_some(!new_value)) {
+            (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, f32::from(u8::from(new_value)));
+            new_value = value;
+            set(&mut self.value, new_value);
+            undo::end_edit(ui.ctx(), parameter, f32::from(u8::from(new_value)));
+            (self.end_set)();
+            response.mark_changed();
+        }
+        response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, true, galley.text()));
+
+        let animated_value = ui
+            .ctx()
+            .animate_bool(format!("button_{id}_light").into(), new_value);
+
+        paint(ui, rect, &response, galley, button_padding, animated_value);
+        paint_learn(
+            ui,
+            rect,
+            ui.style().interact(&response).rounding.ne,
+            format!("button_{id}_learn").into(),
+            parameter,
+        );
+
+        response
+    }
+}
+
+/// whether a momentary button is held, kept outside egui's per-widget memory so it can be released when the
+/// button stops being shown
+#[derive(Clone, Copy, Debug, Default)]
+struct MomentaryState {
+    /// the frame the button was last shown in
+    frame: u64,
+    pressed: bool,
+    /// released while the button wasn't shown, without the value being set back yet
+    released: bool,
+}
+
+fn with_momentary_state<R>(ctx: &Context, id: &str, f: impl FnOnce(&mut MomentaryState) -> R) -> R {
+    ctx.data_mut(|data| {
+        let buttons: &mut HashMap<String, MomentaryState> =
+            data.get_temp_mut_or_default(Id::new(MOMENTARY_ID));
+        f(buttons.entry(id.to_owned()).or_default())
+    })
+}
+
+/// Whether the momentary button `id` was released because it stopped being shown, e.g. when its tab was closed.
+///
+/// Whoever gets `true` has to set the parameter back to `false` and end the gesture, otherwise the button does it
+/// the next time it's shown
+#[must_use]
+pub fn take_released(ctx: &Context, id: &str) -> bool {
+    with_momentary_state(ctx, id, |state| std::mem::take(&mut state.released))
+}
+
+/// releases every momentary button that wasn't shown last frame, since nothing else will.
+/// [`crate::setup`] runs this at the start of every frame
+pub(crate) fn release_hidden_momentary_buttons(ctx: &Context) {
+    let frame = ctx.frame_nr();
+    ctx.data_mut(|data| {
+        let buttons: &mut HashMap<String, MomentaryState> =
+            data.get_temp_mut_or_default(Id::new(MOMENTARY_ID));
+        for state in buttons.values_mut() {
+            if state.frame + 1 < frame && state.pressed {
+                state.pressed = false;
+                state.released = true;
+            }
+        }
+    });
+}
+
+pub fn momentary<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>(
+    id: &str,
+    text: impl Into<WidgetText>,
+    value: GetSet,
+    begin_set: Start,
+    end_set: End,
+) -> Momentary<'_, GetSet, Start, End> {
+    Momentary::new(id, text.into(), value, begin_set, end_set)
+}
+
+/// A button that's only on while it's held down, either with the pointer or with space/enter while focused
+#[must_use]
+pub struct Momentary<'a, GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    description: Option<WidgetText>,
+    value: GetSet,
+    small: bool,
+    text: WidgetText,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
+
+impl<'a, GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>
+    Momentary<'a, GetSet, Start, End>
+{
+    pub fn new(
+        id: &'a str,
+        text: impl Into<WidgetText>,
+        value: GetSet,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameter: None,
+            description: None,
+            value,
+            small: false,
+            text: text.into(),
+            read_only: false,
+            begin_set,
+            end_set,
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub const fn small(mut self) -> Self {
+        self.small = true;
+        self
+    }
+}
+
+impl<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()> Widget
+    for Momentary<'_, GetSet, Start, End>
+{
+    fn ui(mut self, ui: &mut Ui) -> egui::Response {
+        let id = self.id;
+        let (galley, desired_size, button_padding) = layout(ui, self.text, self.small);
+
+        let parameter = self.parameter.unwrap_or(id);
+        let sense = if self.read_only {
+            Sense::hover()
+        } else {
+            Sense::click()
+        };
+        let (rect, mut response) = ui.allocate_at_least(desired_size, sense);
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
+        }
+
+        let value = f32::from(u8::from(get(&mut self.value)));
+        // a held button is in the middle of a gesture, requested values wait until it's let go
+        let held = with_momentary_state(ui.ctx(), id, |state| state.pressed);
+        let requested =
+            registry::register(ui, id, parameter, rect, value, !self.read_only && !held);
+        apply_requested(
+            &mut response,
+            &mut self.value,
+            requested.map(|value| value > 0.5),
+            &self.begin_set,
+            &self.end_set,
+        );
+
+        let keyboard_down = !self.read_only
+            && response.has_focus()
+            && ui.input(|input| input.key_down(Key::Space) || input.key_down(Key::Enter));
+        let pressed = (!self.read_only && response.is_pointer_button_down_on()) || keyboard_down;
+        let frame = ui.ctx().frame_nr();
+        let (was_pressed, released) = with_momentary_state(ui.ctx(), id, |state| {
+            state.frame = frame;
+            let was_pressed = state.pressed;
+            state.pressed = pressed;
+            (was_pressed, std::mem::take(&mut state.released))
+        });
+
+        if released {
+            set(&mut self.value, false);
+            undo::end_edit(ui.ctx(), parameter, 0.0);
+            (self.end_set)();
+            response.mark_changed();
+        }
+        if pressed && !was_pressed {
+            (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, 0.0);
+            set(&mut self.value, true);
+            response.mark_changed();
+        } else if !pressed && was_pressed {
+            set(&mut self.value, false);
+            undo::end_edit(ui.ctx(), parameter, 0.0);
+            (self.end_set)();
+            response.mark_changed();
+        }
+        response
+            .widget_info(|| WidgetInfo::selected(WidgetType::Button, true, pressed, galley.text()));
+
+        let animated_value = ui
+            .ctx()
+            .animate_bool(format!("button_{id}_light").into(), get(&mut self.value));
+
+        paint(ui, rect, &response, galley, button_padding, animated_value);
+
+        response
+    }
+}
+
+pub fn trigger<Action: FnMut(), Start: Fn(), End: Fn()>(
+    id: &str,
+    text: impl Into<WidgetText>,
+    action: Action,
+    begin_set: Start,
+    end_set: End,
+) -> Trigger<'_, Action, Start, End> {
+    Trigger::new(id, text.into(), action, begin_set, end_set)
+}
+
+/// A button that runs `action` once per click, for things like randomizing or resetting.
+/// The light flashes while the button is pressed.
+///
+/// The trigger always registers as `0.0`, and requesting anything above `0.5` through the [`crate::registry`]
+/// runs the action, so it can be MIDI mapped
+#[must_use]
+pub struct Trigger<'a, Action: FnMut(), Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    description: Option<WidgetText>,
+    action: Action,
+    small: bool,
+    text: WidgetText,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
+
+impl<'a, Action: FnMut(), Start: Fn(), End: Fn()> Trigger<'a, Action, Start, End> {
+    pub fn new(
+        id: &'a str,
+        text: impl Into<WidgetText>,
+        action: Action,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameter: None,
+            description: None,
+            action,
+            small: false,
+            text: text.into(),
+            read_only: false,
+            begin_set,
+            end_set,
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the button without letting the user press it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub const fn small(mut self) -> Self {
+        self.small = true;
+        self
+    }
+}
+
+impl<Action: FnMut(), Start: Fn(), End: Fn()> Widget for Trigger<'_, Action, Start, End> {
+    fn ui(mut self, ui: &mut Ui) -> egui::Response {
+        let id = self.id;
+        let (galley, desired_size, button_padding) = layout(ui, self.text, self.small);
+
+        let parameter = self.parameter.unwrap_or(id);
+        let sense = if self.read_only {
+            Sense::hover()
+        } else {
+            Sense::click()
+        };
+        let (rect, mut response) = ui.allocate_at_least(desired_size, sense);
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
+        }
+
+        let requested = registry::register(ui, id, parameter, rect, 0.0, !self.read_only)
+            .is_some_and(|value| value > 0.5);
+        if response.clicked() || requested {
+            (self.begin_set)();
+            (self.action)();
+            (self.end_set)();
+            response.mark_changed();
         }
+        response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, true, galley.text()));
+
+        let animated_value = ui.ctx().animate_bool(
+            format!("button_{id}_light").into(),
+            !self.read_only && (response.is_pointer_button_down_on() || response.clicked()),
+        );
+
+        paint(ui, rect, &response, galley, button_padding, animated_value);
+
+        response
+    }
+}
 
-        let text_wrap_width = 2.0f32.mul_add(-button_padding.x, ui.available_width());
+pub fn checkbox<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>(
+    id: &str,
+    text: impl Into<WidgetText>,
+    value: GetSet,
+    begin_set: Start,
+    end_set: End,
+) -> Checkbox<'_, GetSet, Start, End> {
+    Checkbox::new(id, text.into(), value, begin_set, end_set)
+}
 
-        let galley = self
-            .text
-            .into_galley(ui, None, text_wrap_width, TextStyle::Button);
+/// A compact [`Toggle`] without the button frame, for settings panels
+#[must_use]
+pub struct Checkbox<'a, GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    description: Option<WidgetText>,
+    value: GetSet,
+    text: WidgetText,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
 
-        let mut desired_size = Vec2::ZERO;
-        desired_size.x += galley.size().x + 10.0;
-        desired_size.y = desired_size.y.max(galley.size().y);
-        desired_size += 2.0 * button_padding;
-        if !self.small {
-            desired_size.y = desired_size.y.at_least(ui.spacing().interact_size.y);
+impl<'a, GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()>
+    Checkbox<'a, GetSet, Start, End>
+{
+    pub fn new(
+        id: &'a str,
+        text: impl Into<WidgetText>,
+        value: GetSet,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameter: None,
+            description: None,
+            value,
+            text: text.into(),
+            read_only: false,
+            begin_set,
+            end_set,
         }
+    }
+
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+}
+
+impl<GetSet: FnMut(Operation<bool>) -> bool, Start: Fn(), End: Fn()> Widget
+    for Checkbox<'_, GetSet, Start, End>
+{
+    fn ui(mut self, ui: &mut Ui) -> egui::Response {
+        let id = self.id;
+        let (galley, desired_size) = indicator_layout(ui, self.text);
 
-        let (rect, mut response) = ui.allocate_at_least(desired_size, Sense::click());
+        let parameter = self.parameter.unwrap_or(id);
+        let sense = if self.read_only {
+            Sense::hover()
+        } else {
+            Sense::click()
+        };
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, sense);
         if let Some(description) = self.description {
             response = response.on_hover_text_at_pointer(description);
         }
 
         let mut new_value = get(&mut self.value);
+        let requested = registry::register(
+            ui,
+            id,
+            parameter,
+            rect,
+            f32::from(u8::from(new_value)),
+            !self.read_only,
+        );
+        if let Some(value) = apply_requested(
+            &mut response,
+            &mut self.value,
+            requested.map(|value| value > 0.5),
+            &self.begin_set,
+            &self.end_set,
+        ) {
+            new_value = value;
+        }
         if response.clicked() {
             (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, f32::from(u8::from(new_value)));
             new_value = !new_value;
             set(&mut self.value, new_value);
+            undo::end_edit(ui.ctx(), parameter, f32::from(u8::from(new_value)));
             (self.end_set)();
             response.mark_changed();
         }
-        response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, true, galley.text()));
+        response.widget_info(|| {
+            WidgetInfo::selected(WidgetType::Checkbox, true, new_value, galley.text())
+        });
 
         let animated_value = ui
             .ctx()
-            .animate_bool(format!("button_{id}_light").into(), new_value);
+            .animate_bool(format!("checkbox_{id}_light").into(), new_value);
 
-        if ui.is_rect_visible(rect) {
-            let visuals = ui.style().interact(&response);
-
-            let color = LIGHT_GRADIENT.at(animated_value).to_rgba8();
-            let light_color = Color32::from_rgb(color[0], color[1], color[2]);
-
-            let (frame_expansion, frame_rounding, frame_fill, frame_stroke) = {
-                let expansion = Vec2::splat(visuals.expansion);
-                (
-                    expansion,
-                    visuals.rounding,
-                    visuals.weak_bg_fill,
-                    visuals.bg_stroke,
-                )
-            };
-            ui.painter().rect(
-                rect.expand2(frame_expansion),
-                frame_rounding,
-                frame_fill,
-                frame_stroke,
-            );
+        paint_indicator(ui, rect, &response, galley, animated_value, false);
+
+        response
+    }
+}
 
-            let cursor_x = rect.min.x + button_padding.x + 10.0;
+pub const fn radio_group<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()>(
+    id: &'a str,
+    variants: &'a [(T, &'a str)],
+    value: GetSet,
+    begin_set: Start,
+    end_set: End,
+) -> RadioGroup<'a, T, GetSet, Start, End> {
+    RadioGroup::new(id, variants, value, begin_set, end_set)
+}
 
-            let text_pos = pos2(cursor_x, 0.5f32.mul_add(-galley.size().y, rect.center().y));
-            let light_rect_pos = pos2(rect.min.x + button_padding.x + 3.0, rect.center().y);
-            let light_rect = Rect::from_center_size(light_rect_pos, vec2(4.0, rect.height() - 4.0));
-            ui.painter()
-                .rect(light_rect, Rounding::same(10.0), light_color, Stroke::NONE);
-            ui.painter().galley(text_pos, galley, visuals.text_color());
+/// A list of radio buttons, one per variant, all bound to the same parameter
+#[must_use]
+pub struct RadioGroup<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    description: Option<WidgetText>,
+    variants: &'a [(T, &'a str)],
+    value: GetSet,
+    horizontal: bool,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
+
+impl<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()>
+    RadioGroup<'a, T, GetSet, Start, End>
+{
+    pub const fn new(
+        id: &'a str,
+        variants: &'a [(T, &'a str)],
+        value: GetSet,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameter: None,
+            description: None,
+            variants,
+            value,
+            horizontal: false,
+            read_only: false,
+            begin_set,
+            end_set,
         }
+    }
+
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Lays the radio buttons out in a row instead of a column
+    pub const fn horizontal(mut self) -> Self {
+        self.horizontal = true;
+        self
+    }
+}
+
+impl<T: PartialEq + Copy, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()> Widget
+    for RadioGroup<'_, T, GetSet, Start, End>
+{
+    fn ui(mut self, ui: &mut Ui) -> egui::Response {
+        let id = self.id;
+        let parameter = self.parameter.unwrap_or(id);
+        let sense = if self.read_only {
+            Sense::hover()
+        } else {
+            Sense::click()
+        };
+        let layout = if self.horizontal {
+            Layout::left_to_right(Align::Center)
+        } else {
+            Layout::top_down(Align::Min)
+        };
+
+        let InnerResponse {
+            inner: buttons,
+            mut response,
+        } = ui.with_layout(layout, |ui| {
+            let mut current = get(&mut self.value);
+            let mut buttons = Vec::with_capacity(self.variants.len());
+            for (index, (variant, label)) in self.variants.iter().enumerate() {
+                let (galley, desired_size) = indicator_layout(ui, (*label).into());
+                let (rect, mut button_response) = ui.allocate_exact_size(desired_size, sense);
 
-        if let Some(cursor) = ui.visuals().interact_cursor {
-            if response.hovered {
-                ui.ctx().set_cursor_icon(cursor);
+                if button_response.clicked() && *variant != current {
+                    (self.begin_set)();
+                    undo::begin_edit(ui.ctx(), parameter, variant_value(self.variants, &current));
+                    set(&mut self.value, *variant);
+                    undo::end_edit(ui.ctx(), parameter, variant_value(self.variants, variant));
+                    (self.end_set)();
+                    current = *variant;
+                    button_response.mark_changed();
+                }
+                buttons.push((index, *variant, rect, button_response, galley));
             }
+
+            for (index, variant, rect, button_response, galley) in &buttons {
+                let selected = *variant == current;
+                button_response.widget_info(|| {
+                    WidgetInfo::selected(WidgetType::RadioButton, true, selected, galley.text())
+                });
+
+                let animated_value = ui
+                    .ctx()
+                    .animate_bool(format!("radio_{id}_{index}_light").into(), selected);
+                paint_indicator(
+                    ui,
+                    *rect,
+                    button_response,
+                    galley.clone(),
+                    animated_value,
+                    true,
+                );
+            }
+
+            buttons
+        });
+
+        for (_, _, _, button_response, _) in buttons {
+            response |= button_response;
+        }
+
+        // the buttons have to be laid out before the group's rect is known, so a requested value shows up next frame
+        let current = variant_value(self.variants, &get(&mut self.value));
+        let requested =
+            registry::register(ui, id, parameter, response.rect, current, !self.read_only)
+                .and_then(|value| variant_at(self.variants, value));
+        if apply_requested(
+            &mut response,
+            &mut self.value,
+            requested,
+            &self.begin_set,
+            &self.end_set,
+        )
+        .is_some()
+        {
+            ui.ctx().request_repaint();
+        }
+
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
         }
 
         response
     }
 }
+
+pub(super) fn layout(ui: &Ui, text: WidgetText, small: bool) -> (Arc<Galley>, Vec2, Vec2) {
+    let mut button_padding = ui.spacing().button_padding;
+    if small {
+        button_padding.y = 0.0;
+    }
+
+    let text_wrap_width = 2.0f32.mul_add(-button_padding.x, ui.available_width());
+
+    let galley = text.into_galley(ui, None, text_wrap_width, TextStyle::Button);
+
+    let mut desired_size = Vec2::ZERO;
+    desired_size.x += galley.size().x + 10.0;
+    desired_size.y = desired_size.y.max(galley.size().y);
+    desired_size += 2.0 * button_padding;
+    if !small {
+        desired_size.y = desired_size.y.at_least(ui.spacing().interact_size.y);
+    }
+
+    (galley, desired_size, button_padding)
+}
+
+pub(super) fn paint(
+    ui: &Ui,
+    rect: Rect,
+    response: &Response,
+    galley: Arc<Galley>,
+    button_padding: Vec2,
+    animated_value: f32,
+) {
+    if ui.is_rect_visible(rect) {
+        let visuals = ui.style().interact(response);
+
+        let (frame_expansion, frame_rounding, frame_fill, frame_stroke) = {
+            let expansion = Vec2::splat(visuals.expansion);
+            (
+                expansion,
+                visuals.rounding,
+                visuals.weak_bg_fill,
+                visuals.bg_stroke,
+            )
+        };
+        ui.painter().rect(
+            rect.expand2(frame_expansion),
+            frame_rounding,
+            frame_fill,
+            frame_stroke,
+        );
+
+        let cursor_x = rect.min.x + button_padding.x + 10.0;
+
+        let text_pos = pos2(cursor_x, 0.5f32.mul_add(-galley.size().y, rect.center().y));
+        paint_light(ui.painter(), rect, button_padding, animated_value);
+        ui.painter().galley(text_pos, galley, visuals.text_color());
+    }
+
+    if let Some(cursor) = ui.visuals().interact_cursor {
+        if response.hovered && response.sense.click {
+            ui.ctx().set_cursor_icon(cursor);
+        }
+    }
+}
+
+fn indicator_layout(ui: &Ui, text: WidgetText) -> (Arc<Galley>, Vec2) {
+    let spacing = ui.spacing();
+    let icon_width = spacing.icon_width;
+    let icon_spacing = spacing.icon_spacing;
+    let text_wrap_width = ui.available_width() - icon_width - icon_spacing;
+
+    let galley = text.into_galley(ui, None, text_wrap_width, TextStyle::Button);
+
+    let mut desired_size = vec2(icon_width + icon_spacing + galley.size().x, galley.size().y);
+    desired_size.y = desired_size.y.at_least(spacing.interact_size.y);
+
+    (galley, desired_size)
+}
+
+/// paints a checkbox or radio button: a small frame with the light inside it, then the text
+fn paint_indicator(
+    ui: &Ui,
+    rect: Rect,
+    response: &Response,
+    galley: Arc<Galley>,
+    animated_value: f32,
+    round: bool,
+) {
+    if ui.is_rect_visible(rect) {
+        let visuals = ui.style().interact(response);
+        let icon_width = ui.spacing().icon_width;
+
+        let icon_rect = Rect::from_center_size(
+            pos2(rect.min.x + icon_width / 2.0, rect.center().y),
+            Vec2::splat(icon_width),
+        );
+        let light_rect = icon_rect.shrink(3.0);
+
+        if round {
+            ui.painter().circle(
+                icon_rect.center(),
+                icon_width / 2.0 + visuals.expansion,
+                visuals.weak_bg_fill,
+                visuals.bg_stroke,
+            );
+            ui.painter().circle_filled(
+                light_rect.center(),
+                light_rect.width() / 2.0,
+                light_color(animated_value),
+            );
+        } else {
+            ui.painter().rect(
+                icon_rect.expand(visuals.expansion),
+                visuals.rounding,
+                visuals.weak_bg_fill,
+                visuals.bg_stroke,
+            );
+            ui.painter()
+                .rect_filled(light_rect, Rounding::same(2.0), light_color(animated_value));
+        }
+
+        let text_pos = pos2(
+            icon_rect.max.x + ui.spacing().icon_spacing,
+            0.5f32.mul_add(-galley.size().y, rect.center().y),
+        );
+        ui.painter().galley(text_pos, galley, visuals.text_color());
+    }
+
+    if let Some(cursor) = ui.visuals().interact_cursor {
+        if response.hovered && response.sense.click {
+            ui.ctx().set_cursor_icon(cursor);
+        }
+    }
+}
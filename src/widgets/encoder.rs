@@ -0,0 +1,332 @@
+use egui::{
+    pos2, CursorIcon, Id, Response, Sense, Stroke, Ui, Vec2, Widget, WidgetInfo, WidgetText,
+};
+
+use crate::{
+    colors::{HIGHLIGHT_COL32, PURPLE_COL32, WIDGET_BACKGROUND_COL32},
+    registry, undo,
+    util::get_set::Operation,
+};
+
+use super::{
+    apply_requested, begin_drag_gesture, begin_scroll_gesture, drag_distance, end_drag_gesture,
+    end_scroll_gesture, fine_adjustment, format_value, gesture_open, get,
+    knob::{make_rects, pointer, KnobRects, LabelLayout},
+    scroll_distance, scroll_gesture_active, scrolled_to, set, value_readout, Formatter,
+};
+
+type TurnCallback<'a> = Box<dyn FnMut(f32) + 'a>;
+
+pub const fn encoder<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>(
+    id: &str,
+    diameter: f32,
+    value: GetSet,
+    begin_set: Start,
+    end_set: End,
+) -> Encoder<'_, GetSet, Start, End> {
+    Encoder::new(id, diameter, value, begin_set, end_set)
+}
+
+/// An endless rotary encoder. The pointer goes all the way around and the value wraps from 1.0 back to 0.0,
+/// which suits phase, hue and macro controls
+#[must_use]
+pub struct Encoder<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    label: Option<WidgetText>,
+    description: Option<WidgetText>,
+    formatter: Option<Formatter<'a>>,
+    diameter: f32,
+    detents: Option<u32>,
+    on_turn: Option<TurnCallback<'a>>,
+    value: GetSet,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
+
+impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
+    Encoder<'a, GetSet, Start, End>
+{
+    pub const fn new(
+        id: &'a str,
+        diameter: f32,
+        value: GetSet,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameter: None,
+            label: None,
+            description: None,
+            formatter: None,
+            diameter,
+            detents: None,
+            on_turn: None,
+            value,
+            read_only: false,
+            begin_set,
+            end_set,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<WidgetText>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets how the value is shown in the readout bubble, units included (e.g. `-6.0 dB`)
+    pub fn formatter(mut self, formatter: impl Fn(f32) -> String + 'a) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Makes the encoder click into `detents` steps per turn, like a stepped hardware encoder
+    pub const fn detents(mut self, detents: u32) -> Self {
+        self.detents = Some(detents);
+        self
+    }
+
+    /// Calls `on_turn` with how far the encoder was turned, in turns (positive is clockwise).
+    /// Use this to drive relative controls instead of (or as well as) the wrapped value
+    pub fn on_turn(mut self, on_turn: impl FnMut(f32) + 'a) -> Self {
+        self.on_turn = Some(Box::new(on_turn));
+        self
+    }
+
+    /// turns the encoder by `distance`, snapping to the detents if there are any. returns whether the value changed
+    fn turn(&mut self, ui: &Ui, distance: f32, accumulated_id: Id) -> bool {
+        let detents = self.detents.filter(|detents| *detents > 0);
+        let distance = detents.map_or(distance, |detents| {
+            detent_distance(ui, distance, detents, accumulated_id)
+        });
+
+        if distance == 0.0 {
+            return false;
+        }
+
+        let mut new_value = get(&mut self.value) + distance;
+        if let Some(detents) = detents {
+            #[allow(clippy::cast_precision_loss)]
+            let detents = detents as f32;
+            new_value = (new_value * detents).round() / detents;
+        }
+        set(&mut self.value, new_value.rem_euclid(1.0));
+        if let Some(on_turn) = &mut self.on_turn {
+            on_turn(distance);
+        }
+
+        true
+    }
+}
+
+impl<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
+    for Encoder<'_, GetSet, Start, End>
+{
+    #[allow(clippy::too_many_lines)]
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let parameter = self.parameter.unwrap_or(id);
+        let accumulated_id = format!("encoder_{id}_accumulated").into();
+        let scroll_gesture_id = format!("encoder_{id}_scroll").into();
+        let drag_gesture_id = format!("encoder_{id}_drag").into();
+
+        let interactive = ui.is_enabled() && !self.read_only;
+        let sense = if interactive {
+            Sense::click_and_drag()
+        } else {
+            Sense::hover()
+        };
+        let label_text = self
+            .label
+            .as_ref()
+            .map_or_else(String::new, |label| label.text().to_owned());
+        let KnobRects {
+            galley,
+            full_rect,
+            mut response,
+            rect,
+            text_rect,
+            ..
+        } = make_rects(
+            self.diameter,
+            self.label.take(),
+            self.description.take(),
+            &LabelLayout::new(),
+            sense,
+            ui,
+        );
+        let hovered = interactive && (response.hovered() || response.dragged());
+
+        let value = get(&mut self.value);
+        let requested = registry::register(
+            ui,
+            id,
+            parameter,
+            full_rect,
+            value,
+            !self.read_only && !gesture_open(ui, drag_gesture_id, scroll_gesture_id),
+        );
+        apply_requested(
+            &mut response,
+            &mut self.value,
+            requested.map(|value| value.rem_euclid(1.0)),
+            &self.begin_set,
+            &self.end_set,
+        );
+
+        if end_scroll_gesture(ui, scroll_gesture_id, response.drag_started()) {
+            undo::end_edit(ui.ctx(), parameter, get(&mut self.value));
+            (self.end_set)();
+        }
+
+        if response.drag_started() {
+            ui.ctx().set_cursor_icon(CursorIcon::None);
+            ui.memory_mut(|mem| mem.data.remove::<f32>(accumulated_id));
+            begin_drag_gesture(ui, drag_gesture_id);
+            (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+        }
+
+        if response.dragged() {
+            let drag_delta = response.drag_delta();
+            let granular = fine_adjustment(ui);
+            let distance = drag_distance(ui, -(drag_delta.x + drag_delta.y), granular);
+            if self.turn(ui, distance, accumulated_id) {
+                response.mark_changed();
+            }
+        } else if interactive
+            && response.hovered()
+            && response
+                .ctx
+                .input(|input| input.raw_scroll_delta.length() > 0.0)
+        {
+            if begin_scroll_gesture(ui, scroll_gesture_id, parameter) {
+                ui.memory_mut(|mem| mem.data.remove::<f32>(accumulated_id));
+                (self.begin_set)();
+                undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+            }
+            let scroll_delta = response.ctx.input(|input| input.smooth_scroll_delta);
+            let granular = fine_adjustment(ui);
+            let distance = scroll_distance(ui, -(scroll_delta.x + scroll_delta.y), granular);
+            if self.turn(ui, distance, accumulated_id) {
+                scrolled_to(ui, scroll_gesture_id, get(&mut self.value));
+                response.mark_changed();
+            }
+        }
+
+        if end_drag_gesture(ui, drag_gesture_id, response.dragged()) {
+            ui.ctx().set_cursor_icon(CursorIcon::Default);
+            undo::end_edit(ui.ctx(), parameter, get(&mut self.value));
+            (self.end_set)();
+        }
+
+        let value = get(&mut self.value);
+        response.widget_info(|| WidgetInfo::slider(interactive, f64::from(value), &label_text));
+
+        let readout_value = get(&mut self.value);
+        value_readout(
+            ui,
+            &response,
+            format!("encoder_{id}_readout").into(),
+            response.dragged() || scroll_gesture_active(ui, scroll_gesture_id),
+            || format_value(self.formatter.as_ref(), readout_value),
+        );
+
+        if ui.is_rect_visible(full_rect) {
+            let radius = (self.diameter * 0.75) / 2.0;
+            let background_radius = self.diameter / 2.0;
+            let focus_ring_radius = (self.diameter * 0.90) / 2.0;
+
+            let painter = ui.painter_at(rect);
+            let center = rect.center();
+
+            let animated_hover = ui
+                .ctx()
+                .animate_bool(format!("encoder_{id}_hover").into(), hovered);
+
+            painter.circle_filled(center, background_radius, WIDGET_BACKGROUND_COL32);
+            painter.circle_stroke(center, radius, Stroke::new(radius * 0.1, HIGHLIGHT_COL32));
+
+            if let Some(detents) = self.detents.filter(|detents| *detents <= 64) {
+                for detent in 0..detents {
+                    #[allow(clippy::cast_precision_loss)]
+                    let angle = (detent as f32 / detents as f32).mul_add(-360.0, 90.0);
+                    let (sin, cos) = angle.to_radians().sin_cos();
+                    let direction = Vec2::new(cos, -sin);
+                    painter.line_segment(
+                        [
+                            center + direction * radius * 1.1,
+                            center + direction * radius * 1.2,
+                        ],
+                        Stroke::new(1.0, PURPLE_COL32),
+                    );
+                }
+            }
+
+            // zero is at the top, and the value goes clockwise from there
+            let angle = value.mul_add(-360.0, 90.0);
+            let (sin, cos) = angle.to_radians().sin_cos();
+            pointer(
+                &painter,
+                center,
+                Vec2::new(cos, -sin),
+                radius,
+                background_radius,
+            );
+
+            painter.circle_stroke(
+                center,
+                focus_ring_radius,
+                Stroke::new(
+                    focus_ring_radius * 0.07,
+                    PURPLE_COL32.gamma_multiply(animated_hover),
+                ),
+            );
+
+            if let (Some(text_rect), Some(galley)) = (text_rect, galley) {
+                ui.painter().galley(
+                    pos2(
+                        text_rect.center().x - galley.size().x / 2.0,
+                        0.5f32.mul_add(-galley.size().y, text_rect.center().y),
+                    ),
+                    galley,
+                    ui.visuals().text_color(),
+                );
+            }
+        }
+
+        response
+    }
+}
+
+/// collects `distance` until it adds up to at least one detent, returning how many detents' worth to turn
+fn detent_distance(ui: &Ui, distance: f32, detents: u32, accumulated_id: Id) -> f32 {
+    #[allow(clippy::cast_precision_loss)]
+    let step = 1.0 / detents as f32;
+    let accumulated = ui.memory(|mem| mem.data.get_temp(accumulated_id).unwrap_or(0.0)) + distance;
+    let steps = (accumulated / step).trunc();
+    ui.memory_mut(|mem| {
+        mem.data
+            .insert_temp(accumulated_id, steps.mul_add(-step, accumulated));
+    });
+    steps * step
+}
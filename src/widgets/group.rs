@@ -0,0 +1,147 @@
+use egui::{
+    collapsing_header::CollapsingState, Align, Frame, InnerResponse, Layout, Margin, Rounding,
+    Stroke, Ui, Vec2, WidgetText,
+};
+
+use crate::{colors::PURPLE_COL32, util::get_set::Operation};
+
+use super::{button::toggle, get};
+
+/// how far apart the widgets in a group are, relative to the knob diameter
+const SPACING_PER_DIAMETER: f32 = 0.25;
+
+type BypassValue<'a> = Box<dyn FnMut(Operation<bool>) -> bool + 'a>;
+
+pub fn group(id: &str, title: impl Into<WidgetText>) -> Group<'_> {
+    Group::new(id, title)
+}
+
+struct Bypass<'a> {
+    value: BypassValue<'a>,
+    begin_set: Box<dyn Fn() + 'a>,
+    end_set: Box<dyn Fn() + 'a>,
+}
+
+/// A titled section of a plugin UI, like "Filter" or "Envelope". The header can have a bypass toggle,
+/// and the body can be collapsed.
+///
+/// The widgets in the body are spaced relative to the size of the knobs in it, so sections line up
+#[must_use]
+pub struct Group<'a> {
+    id: &'a str,
+    title: WidgetText,
+    bypass: Option<Bypass<'a>>,
+    collapsible: bool,
+    default_open: bool,
+    knob_diameter: f32,
+}
+
+impl<'a> Group<'a> {
+    pub fn new(id: &'a str, title: impl Into<WidgetText>) -> Self {
+        Self {
+            id,
+            title: title.into(),
+            bypass: None,
+            collapsible: false,
+            default_open: true,
+            knob_diameter: 50.0,
+        }
+    }
+
+    /// Adds a bypass [`Toggle`](super::button::Toggle) to the header. The body is dimmed while the section is bypassed,
+    /// but it can still be edited
+    pub fn bypass<GetSet: FnMut(Operation<bool>) -> bool + 'a, Start: Fn() + 'a, End: Fn() + 'a>(
+        mut self,
+        value: GetSet,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        self.bypass = Some(Bypass {
+            value: Box::new(value),
+            begin_set: Box::new(begin_set),
+            end_set: Box::new(end_set),
+        });
+        self
+    }
+
+    /// Lets the body be collapsed from the header. The open state is remembered between frames
+    pub const fn collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+
+    /// Whether a collapsible group starts out open. Defaults to `true`
+    pub const fn default_open(mut self, default_open: bool) -> Self {
+        self.default_open = default_open;
+        self
+    }
+
+    /// Sets the diameter of the knobs in the group, which the spacing between widgets is based on
+    pub const fn knob_diameter(mut self, diameter: f32) -> Self {
+        self.knob_diameter = diameter;
+        self
+    }
+
+    /// Shows the group with `add_contents` as its body. The inner value is `None` while the group is collapsed
+    pub fn show<R>(
+        mut self,
+        ui: &mut Ui,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> InnerResponse<Option<R>> {
+        let id = self.id;
+        let spacing = self.knob_diameter * SPACING_PER_DIAMETER;
+
+        Frame::group(ui.style())
+            .fill(ui.visuals().extreme_bg_color)
+            .stroke(Stroke::new(1.0, PURPLE_COL32))
+            .rounding(Rounding::same(4.0))
+            .inner_margin(Margin::same(spacing))
+            .show(ui, |ui| {
+                let bypassed = self
+                    .bypass
+                    .as_mut()
+                    .is_some_and(|bypass| get(&mut bypass.value));
+                let add_body = |ui: &mut Ui| {
+                    if bypassed {
+                        ui.multiply_opacity(0.5);
+                    }
+                    ui.spacing_mut().item_spacing = Vec2::splat(spacing);
+                    add_contents(ui)
+                };
+
+                if self.collapsible {
+                    CollapsingState::load_with_default_open(
+                        ui.ctx(),
+                        format!("group_{id}_collapsing").into(),
+                        self.default_open,
+                    )
+                    .show_header(ui, |ui| self.header(ui))
+                    .body_unindented(add_body)
+                    .2
+                    .map(|body| body.inner)
+                } else {
+                    ui.horizontal(|ui| self.header(ui));
+                    Some(add_body(ui))
+                }
+            })
+    }
+
+    fn header(&mut self, ui: &mut Ui) {
+        let id = self.id;
+        ui.label(self.title.clone().strong());
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            if let Some(bypass) = &mut self.bypass {
+                ui.add(
+                    toggle(
+                        &format!("group_{id}_bypass"),
+                        "Bypass",
+                        &mut bypass.value,
+                        &bypass.begin_set,
+                        &bypass.end_set,
+                    )
+                    .small(),
+                );
+            }
+        });
+    }
+}
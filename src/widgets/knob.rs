@@ -1,19 +1,29 @@
+use std::sync::Arc;
+
 use colorgrad::{BasisGradient, Color, Gradient, GradientBuilder};
 use egui::{
-    epaint::PathShape, pos2, remap_clamp, Color32, Painter, Response, Sense, Stroke, TextStyle, Ui,
-    Vec2, Widget, WidgetText,
+    epaint::PathShape, pos2, remap_clamp, Color32, Galley, Image, ImageSource, Painter, Pos2, Rect,
+    Response, Sense, Stroke, TextStyle, TextWrapMode, Ui, Vec2, Widget, WidgetInfo, WidgetText,
 };
 use once_cell::sync::Lazy;
 
 use crate::{
-    colors::{HIGHLIGHT, PURPLE_COL32, WIDGET_BACKGROUND_COL32},
-    util::{generate_arc, get_set::Operation},
+    colors::{HIGHLIGHT, MODULATION_COL32, PURPLE_COL32, WIDGET_BACKGROUND_COL32},
+    interaction::interaction_settings,
+    registry, undo,
+    util::{generate_arc, generate_sector, get_set::Operation},
 };
 
-use super::{get, set};
+use super::{
+    apply_requested, begin_drag_gesture, begin_scroll_gesture, begin_warped_drag, drag_distance,
+    end_drag_gesture, end_scroll_gesture, end_warped_drag, fine_adjustment, format_value,
+    gesture_open, get, paint_learn, parameter_menu, reset_requested, scroll_gesture_active,
+    scroll_normalized, scrolled_to, set, value_readout, warped_drag_delta, AutomationState,
+    Formatter,
+};
 
-const START_DEG: f32 = 225.0;
-const END_DEG: f32 = -45.0;
+/// the classic 270° sweep, from 225° to -45°
+const DEFAULT_SWEEP_DEG: f32 = 270.0;
 
 static TRACK_GRADIENT: Lazy<BasisGradient> = Lazy::new(|| {
     GradientBuilder::new()
@@ -23,6 +33,116 @@ static TRACK_GRADIENT: Lazy<BasisGradient> = Lazy::new(|| {
         .unwrap()
 });
 
+/// How a [`Knob`] is drawn. Every style shares the same interaction, focus ring and modulation arc
+#[derive(Clone, Debug, Default)]
+pub enum KnobStyle<'a> {
+    /// The four-lobed star with a track around it and a pointer
+    #[default]
+    Classic,
+    /// Just a ring that fills up with the value
+    Arc,
+    /// A pie that fills up with the value, with a pointer
+    Filled,
+    /// A dot near the edge pointing at the value
+    Minimal,
+    /// Picks a frame from a sprite sheet, see [`FilmStrip`]
+    FilmStrip(FilmStrip<'a>),
+}
+
+/// Where a [`Knob`]'s label goes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LabelPosition {
+    Above,
+    #[default]
+    Below,
+    /// The label isn't shown, it's only used for accessibility
+    None,
+}
+
+/// how the label of a knob-shaped widget is laid out
+#[derive(Clone, Debug)]
+pub(super) struct LabelLayout {
+    pub position: LabelPosition,
+    pub text_style: TextStyle,
+    pub truncate: bool,
+    pub value_line: bool,
+}
+
+impl LabelLayout {
+    pub const fn new() -> Self {
+        Self {
+            position: LabelPosition::Below,
+            text_style: TextStyle::Body,
+            truncate: false,
+            value_line: false,
+        }
+    }
+}
+
+/// Which pointer movement turns a [`Knob`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DragMode {
+    /// Up and left turn the knob up, down and right turn it down. This is how knobs have always behaved here,
+    /// so dragging along either axis works, but it's the opposite of [`DragMode::Horizontal`] on the horizontal axis
+    #[default]
+    Combined,
+    /// Only up and down count, up turns the knob up
+    Vertical,
+    /// Only left and right count, right turns the knob up like a horizontal slider
+    Horizontal,
+    /// The knob turns with the pointer as it goes around the knob's center
+    Rotary,
+}
+
+/// A sprite sheet of pre-rendered knob frames, from the minimum value to the maximum.
+///
+/// The image is loaded through egui's image loaders, so `egui_extras::install_image_loaders` has to have been called
+#[derive(Clone, Debug)]
+pub struct FilmStrip<'a> {
+    source: ImageSource<'a>,
+    frames: u32,
+    horizontal: bool,
+}
+
+impl<'a> FilmStrip<'a> {
+    /// A sprite sheet with `frames` frames stacked on top of each other
+    pub fn new(source: impl Into<ImageSource<'a>>, frames: u32) -> Self {
+        Self {
+            source: source.into(),
+            frames: frames.max(1),
+            horizontal: false,
+        }
+    }
+
+    /// Lays the frames out left to right instead
+    #[must_use]
+    pub const fn horizontal(mut self) -> Self {
+        self.horizontal = true;
+        self
+    }
+
+    fn paint(&self, ui: &Ui, rect: Rect, value: f32) {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let frame = (value * (self.frames - 1) as f32).round() as u32;
+        #[allow(clippy::cast_precision_loss)]
+        let (start, end) = (
+            frame as f32 / self.frames as f32,
+            (frame + 1) as f32 / self.frames as f32,
+        );
+        let uv = if self.horizontal {
+            Rect::from_min_max(pos2(start, 0.0), pos2(end, 1.0))
+        } else {
+            Rect::from_min_max(pos2(0.0, start), pos2(1.0, end))
+        };
+
+        Image::new(self.source.clone()).uv(uv).paint_at(ui, rect);
+    }
+}
+
 pub const fn knob<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>(
     id: &str,
     diameter: f32,
@@ -36,14 +156,24 @@ pub const fn knob<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>(
 #[must_use]
 pub struct Knob<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> {
     id: &'a str,
+    parameter: Option<&'a str>,
     label: Option<WidgetText>,
     description: Option<WidgetText>,
+    formatter: Option<Formatter<'a>>,
     diameter: f32,
     value: GetSet,
     begin_set: Start,
     end_set: End,
     default: Option<f32>,
     modulated: Option<f32>,
+    style: KnobStyle<'a>,
+    sweep: f32,
+    automation: AutomationState,
+    locked: bool,
+    read_only: bool,
+    infinite_drag: bool,
+    drag_mode: DragMode,
+    label_layout: LabelLayout,
 }
 
 impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
@@ -58,14 +188,24 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
     ) -> Self {
         Self {
             id,
+            parameter: None,
             diameter,
             value,
             begin_set,
             end_set,
             label: None,
             description: None,
+            formatter: None,
             default: None,
             modulated: None,
+            style: KnobStyle::Classic,
+            sweep: DEFAULT_SWEEP_DEG,
+            automation: AutomationState::None,
+            locked: false,
+            read_only: false,
+            infinite_drag: false,
+            drag_mode: DragMode::Combined,
+            label_layout: LabelLayout::new(),
         }
     }
 
@@ -79,6 +219,19 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
         self
     }
 
+    /// Sets how the value is shown in the readout bubble, units included (e.g. `-6.0 dB`)
+    pub fn formatter(mut self, formatter: impl Fn(f32) -> String + 'a) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Sets the value the knob resets to when the user performs the reset gesture (see [`crate::interaction::InteractionSettings::reset_gesture`])
     pub const fn default_value(mut self, default: f32) -> Self {
         self.default = Some(default);
         self
@@ -88,6 +241,196 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
         self.modulated = Some(value);
         self
     }
+
+    /// Sets how many degrees the knob turns from its minimum to its maximum, centered on the top.
+    /// e.g. 300 for a classic hardware knob, 180 for a half-moon or 360 for a full circle
+    pub const fn sweep(mut self, degrees: f32) -> Self {
+        self.sweep = degrees;
+        self
+    }
+
+    pub fn style(mut self, style: KnobStyle<'a>) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Shows the host's automation state for the parameter
+    pub const fn automation(mut self, automation: AutomationState) -> Self {
+        self.automation = automation;
+        self
+    }
+
+    /// Locks the knob while automation is playing back. It still shows the value but ignores the pointer
+    pub const fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Keeps the drag going when the pointer reaches the edge of the screen, by warping the pointer back to where
+    /// the drag started. When the drag is over the pointer is put back where the drag started, which is wherever
+    /// on the knob it was grabbed rather than the knob's center. Off by default.
+    ///
+    /// The warp is sent as [`egui::ViewportCommand::CursorPosition`], which only works if the integration handles it.
+    /// eframe does on most platforms, but egui-baseview doesn't, and without the warp the knob keeps turning
+    /// as long as the pointer is away from where the drag started. Only turn this on where the warp is supported
+    pub const fn infinite_drag(mut self, infinite_drag: bool) -> Self {
+        self.infinite_drag = infinite_drag;
+        self
+    }
+
+    /// Puts the label above or below the knob, or hides it
+    pub const fn label_position(mut self, position: LabelPosition) -> Self {
+        self.label_layout.position = position;
+        self
+    }
+
+    /// Sets the text style of the label and the value line. Defaults to [`TextStyle::Body`]
+    pub fn label_style(mut self, text_style: TextStyle) -> Self {
+        self.label_layout.text_style = text_style;
+        self
+    }
+
+    /// Cuts labels wider than the knob short with an ellipsis instead of widening the knob, for tight grids
+    pub const fn truncate_label(mut self, truncate: bool) -> Self {
+        self.label_layout.truncate = truncate;
+        self
+    }
+
+    /// Adds a line under the label that shows the formatted value while the knob is being changed.
+    /// The line's space is always kept so the layout doesn't jump. Without it the value takes the label's place
+    /// while the knob is being changed
+    pub const fn value_line(mut self, value_line: bool) -> Self {
+        self.label_layout.value_line = value_line;
+        self
+    }
+
+    /// Sets which pointer movement turns the knob. Infinite drag doesn't apply to [`DragMode::Rotary`]
+    pub const fn drag_mode(mut self, drag_mode: DragMode) -> Self {
+        self.drag_mode = drag_mode;
+        self
+    }
+}
+
+impl<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Knob<'_, GetSet, Start, End> {
+    /// the angles (in degrees, counterclockwise from the right) the minimum and maximum values point at
+    fn sweep_angles(&self) -> (f32, f32) {
+        let half_sweep = self.sweep.clamp(1.0, 360.0) / 2.0;
+        (90.0 + half_sweep, 90.0 - half_sweep)
+    }
+
+    /// whether the pointer is warped back while dragging. rotary drags need to know where the pointer really is
+    fn warps_pointer(&self) -> bool {
+        self.infinite_drag && self.drag_mode != DragMode::Rotary
+    }
+
+    /// how far the value moves when the pointer moves by `drag_delta`, following the drag mode
+    fn drag_distance(&self, ui: &Ui, center: Pos2, drag_delta: Vec2, granular: bool) -> f32 {
+        let delta = match self.drag_mode {
+            DragMode::Combined => -(drag_delta.x + drag_delta.y),
+            DragMode::Vertical => -drag_delta.y,
+            DragMode::Horizontal => drag_delta.x,
+            DragMode::Rotary => return self.rotary_distance(ui, center, drag_delta, granular),
+        };
+        drag_distance(ui, delta, granular)
+    }
+
+    /// how far the value moves as the pointer goes around `center`, so the knob follows the pointer
+    fn rotary_distance(&self, ui: &Ui, center: Pos2, drag_delta: Vec2, granular: bool) -> f32 {
+        let Some(position) = ui.input(|input| input.pointer.latest_pos()) else {
+            return 0.0;
+        };
+        // right at the center the angle jumps around with the slightest movement
+        if position.distance(center) < self.diameter * 0.1 {
+            return 0.0;
+        }
+
+        let angle = |position: Pos2| {
+            let offset = position - center;
+            (-offset.y).atan2(offset.x).to_degrees()
+        };
+        // the value goes up clockwise, which is towards smaller angles
+        let turned =
+            (angle(position - drag_delta) - angle(position) + 180.0).rem_euclid(360.0) - 180.0;
+        let fine_factor = if granular {
+            interaction_settings(ui.ctx()).fine_factor.max(1.0)
+        } else {
+            1.0
+        };
+        turned / (self.sweep.clamp(1.0, 360.0) * fine_factor)
+    }
+
+    fn value_angle(&self, value: f32) -> f32 {
+        let (start, end) = self.sweep_angles();
+        remap_clamp(value, 0.0..=1.0, start..=end)
+    }
+
+    /// everything between the background and the focus ring, which is what the styles differ in
+    fn paint_face(&self, painter: &Painter, center: Pos2, value_angle: f32, stroke_color: Color32) {
+        let radius = (self.diameter * 0.75) / 2.0;
+        let background_radius = self.diameter / 2.0;
+        let (start, end) = self.sweep_angles();
+        let (tick_sin, tick_cos) = value_angle.to_radians().sin_cos();
+        let direction = Vec2::new(tick_cos, -tick_sin);
+
+        match self.style {
+            KnobStyle::Classic => {
+                generate_arc(
+                    painter,
+                    center,
+                    radius,
+                    start.to_radians(),
+                    end.to_radians(),
+                    Stroke::new(radius * 0.1, stroke_color),
+                );
+
+                star(painter, center, value_angle, self.diameter);
+                pointer(painter, center, direction, radius, background_radius);
+            }
+            KnobStyle::Arc => {
+                generate_arc(
+                    painter,
+                    center,
+                    radius,
+                    start.to_radians(),
+                    end.to_radians(),
+                    Stroke::new(radius * 0.1, PURPLE_COL32),
+                );
+                generate_arc(
+                    painter,
+                    center,
+                    radius,
+                    start.to_radians(),
+                    value_angle.to_radians(),
+                    Stroke::new(radius * 0.2, stroke_color),
+                );
+            }
+            KnobStyle::Filled => {
+                generate_sector(
+                    painter,
+                    center,
+                    radius,
+                    start.to_radians(),
+                    value_angle.to_radians(),
+                    stroke_color,
+                );
+                pointer(painter, center, direction, radius, background_radius);
+            }
+            KnobStyle::Minimal => {
+                painter.circle_filled(
+                    center + direction * radius * 0.8,
+                    background_radius * 0.1,
+                    Color32::WHITE,
+                );
+            }
+            KnobStyle::FilmStrip(_) => {}
+        }
+    }
 }
 
 impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
@@ -95,65 +438,146 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
 {
     #[allow(clippy::too_many_lines)]
     fn ui(mut self, ui: &mut Ui) -> Response {
-        let (galley, full_rect, mut response, rect, text_rect) =
-            make_rects(self.diameter, self.label, self.description, ui);
+        let id = self.id;
+        let parameter = self.parameter.unwrap_or(id);
+        let interactive = ui.is_enabled() && !self.locked && !self.read_only;
+        let sense = if interactive {
+            Sense::click_and_drag()
+        } else {
+            Sense::hover()
+        };
+        let label_text = self
+            .label
+            .as_ref()
+            .map_or_else(String::new, |label| label.text().to_owned());
+        let KnobRects {
+            galley,
+            full_rect,
+            mut response,
+            rect,
+            text_rect,
+            value_rect,
+        } = make_rects(
+            self.diameter,
+            self.label.take(),
+            self.description.take(),
+            &self.label_layout,
+            sense,
+            ui,
+        );
+        if self.locked {
+            response = response.on_hover_text_at_pointer("locked by automation");
+        }
         let mut granular = false;
-        let hovered = response.hovered() || response.dragged();
+        let hovered = interactive && (response.hovered() || response.dragged());
+        let scroll_gesture_id = format!("knob_{id}_scroll").into();
+        let drag_origin_id = format!("knob_{id}_drag_origin").into();
+        let drag_gesture_id = format!("knob_{id}_drag").into();
+
+        let value = get(&mut self.value);
+        let requested = registry::register(
+            ui,
+            id,
+            parameter,
+            full_rect,
+            value,
+            !self.locked
+                && !self.read_only
+                && !gesture_open(ui, drag_gesture_id, scroll_gesture_id),
+        );
+        apply_requested(
+            &mut response,
+            &mut self.value,
+            requested,
+            &self.begin_set,
+            &self.end_set,
+        );
 
-        if let Some(default) = self.default {
-            if response.double_clicked() {
-                (self.begin_set)();
-                set(&mut self.value, default);
-                (self.end_set)();
-            }
+        let menu_value = parameter_menu(&response, parameter, get(&mut self.value), self.default);
+        let reset_default = self.default.filter(|_| reset_requested(ui, &response));
+        if let Some(new_value) = menu_value.or(reset_default) {
+            (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+            set(&mut self.value, new_value);
+            undo::end_edit(ui.ctx(), parameter, new_value);
+            (self.end_set)();
+            response.mark_changed();
         }
 
-        if response.hovered() {
-            granular = response.ctx.input(|i| i.modifiers.shift);
+        if hovered {
+            granular = fine_adjustment(ui);
+        }
+
+        if end_scroll_gesture(ui, scroll_gesture_id, response.drag_started()) {
+            undo::end_edit(ui.ctx(), parameter, get(&mut self.value));
+            (self.end_set)();
         }
 
         if response.drag_started() {
-            ui.ctx().set_cursor_icon(egui::CursorIcon::None);
+            if self.drag_mode != DragMode::Rotary {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::None);
+            }
+            if self.warps_pointer() {
+                begin_warped_drag(ui, drag_origin_id);
+            }
+            begin_drag_gesture(ui, drag_gesture_id);
             (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
         }
 
         if response.dragged() {
-            let drag_delta = response.drag_delta();
-            granular = response.ctx.input(|i| i.modifiers.shift);
-            let diameter_scale = if granular { 4.0 } else { 2.0 };
-
-            let delta = -(drag_delta.x + drag_delta.y);
-            let mut new_value = get(&mut self.value);
-            new_value += delta / (self.diameter * diameter_scale);
-            new_value = new_value.clamp(0.0, 1.0);
+            let drag_delta = if self.warps_pointer() {
+                warped_drag_delta(ui, drag_origin_id)
+            } else {
+                response.drag_delta()
+            };
+            granular = fine_adjustment(ui);
+
+            let distance = self.drag_distance(ui, rect.center(), drag_delta, granular);
+            let new_value = (get(&mut self.value) + distance).clamp(0.0, 1.0);
             set(&mut self.value, new_value);
 
             response.mark_changed();
-        } else if response.hovered()
+        } else if interactive
+            && response.hovered()
             && response
                 .ctx
                 .input(|input| input.raw_scroll_delta.length() > 0.0)
         {
-            (self.begin_set)();
+            if begin_scroll_gesture(ui, scroll_gesture_id, parameter) {
+                (self.begin_set)();
+                undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+            }
             let drag_delta = response.ctx.input(|input| input.smooth_scroll_delta);
-            granular = response.ctx.input(|i| i.modifiers.shift);
-            let diameter_scale = if granular { 8.0 } else { 4.0 };
+            granular = fine_adjustment(ui);
 
             let delta = -(drag_delta.x + drag_delta.y);
-            let mut new_value = get(&mut self.value);
-            new_value += delta / (self.diameter * diameter_scale);
-            new_value = new_value.clamp(0.0, 1.0);
+            let new_value = scroll_normalized(ui, get(&mut self.value), delta, granular);
             set(&mut self.value, new_value);
+            scrolled_to(ui, scroll_gesture_id, new_value);
 
             response.mark_changed();
-            (self.end_set)();
         }
 
-        if response.drag_stopped() {
+        if end_drag_gesture(ui, drag_gesture_id, response.dragged()) {
             ui.ctx().set_cursor_icon(egui::CursorIcon::Default);
+            end_warped_drag(ui, drag_origin_id);
+            undo::end_edit(ui.ctx(), parameter, get(&mut self.value));
             (self.end_set)();
         }
 
+        let readout_value = get(&mut self.value);
+        response
+            .widget_info(|| WidgetInfo::slider(interactive, f64::from(readout_value), &label_text));
+        let changing = response.dragged() || scroll_gesture_active(ui, scroll_gesture_id);
+        value_readout(
+            ui,
+            &response,
+            format!("knob_{id}_readout").into(),
+            changing,
+            || format_value(self.formatter.as_ref(), readout_value),
+        );
+
         if ui.is_rect_visible(full_rect) {
             let value = get(&mut self.value);
 
@@ -161,8 +585,9 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
             let background_radius = self.diameter / 2.0;
             let focus_ring_radius = (self.diameter * 0.90) / 2.0;
 
-            let painter = Painter::new(ui.ctx().clone(), ui.layer_id(), rect);
-            let id = self.id;
+            // painting through the ui's painter greys the knob out along with the rest of a disabled ui
+            let painter = ui.painter_at(rect);
+            let center = rect.center();
 
             let animated_granular = ui
                 .ctx()
@@ -173,140 +598,191 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
             let color = TRACK_GRADIENT.at(animated_granular).to_rgba8();
             let stroke_color = Color32::from_rgb(color[0], color[1], color[2]);
 
-            painter.circle_filled(
-                painter.clip_rect().center(),
-                background_radius,
-                WIDGET_BACKGROUND_COL32,
-            );
-
-            generate_arc(
-                &painter,
-                painter.clip_rect().center(),
-                radius,
-                225.0_f32.to_radians(),
-                -45.0_f32.to_radians(),
-                Stroke::new(radius * 0.1, stroke_color),
-            );
-
-            let value_angle = remap_clamp(value, 0.0..=1.0, START_DEG..=END_DEG);
+            let value_angle = self.value_angle(value);
 
-            star(&painter, value_angle, self.diameter);
+            if let KnobStyle::FilmStrip(film_strip) = &self.style {
+                film_strip.paint(ui, rect, value);
+            } else {
+                painter.circle_filled(center, background_radius, WIDGET_BACKGROUND_COL32);
+                self.paint_face(&painter, center, value_angle, stroke_color);
+            }
 
             if let Some(modulated) = self.modulated {
-                let modulated_angle = remap_clamp(modulated, 0.0..=1.0, START_DEG..=END_DEG);
+                let modulated_angle = self.value_angle(modulated);
 
                 generate_arc(
                     &painter,
-                    painter.clip_rect().center(),
+                    center,
                     radius * 0.75,
                     value_angle.to_radians(),
                     modulated_angle.to_radians(),
-                    Stroke::new(radius * 0.1, Color32::from_rgb(175, 48, 255)),
+                    Stroke::new(radius * 0.1, MODULATION_COL32),
                 );
             }
 
             painter.circle_stroke(
-                painter.clip_rect().center(),
+                center,
                 focus_ring_radius,
                 Stroke::new(
                     focus_ring_radius * 0.07,
-                    PURPLE_COL32.gamma_multiply(animated_hover),
+                    self.automation
+                        .color()
+                        .unwrap_or_else(|| PURPLE_COL32.gamma_multiply(animated_hover)),
                 ),
             );
 
-            let (tick_sin, tick_cos) = value_angle.to_radians().sin_cos();
-            let first_point = painter.clip_rect().center()
-                + Vec2::new(radius * 0.5 * tick_cos, radius * 0.5 * -tick_sin);
-            let second_point =
-                painter.clip_rect().center() + Vec2::new(radius * tick_cos, radius * -tick_sin);
-            painter.line_segment(
-                [first_point, second_point],
-                Stroke::new(background_radius * 0.15, Color32::WHITE),
-            );
-            painter.circle_filled(first_point, background_radius * 0.07, Color32::WHITE);
-            painter.circle_filled(second_point, background_radius * 0.07, Color32::WHITE);
-
-            if let Some(text_rect) = text_rect {
-                if let Some(galley) = galley {
-                    ui.painter().galley(
-                        pos2(
-                            text_rect.center().x - galley.size().x / 2.0,
-                            0.5f32.mul_add(-galley.size().y, text_rect.center().y),
-                        ),
-                        galley,
-                        Color32::WHITE,
+            // while the knob is being changed the value goes on its own line if there is one, or else over the label
+            let swapped = changing && value_rect.is_none();
+            if let (Some(text_rect), Some(galley)) = (text_rect, galley.filter(|_| !swapped)) {
+                paint_centered(ui, text_rect, galley);
+            }
+            if let Some(value_rect) = value_rect.or(text_rect).filter(|_| changing) {
+                let galley = WidgetText::from(format_value(self.formatter.as_ref(), value))
+                    .into_galley(
+                        ui,
+                        Some(TextWrapMode::Truncate),
+                        value_rect.width(),
+                        self.label_layout.text_style.clone(),
                     );
-                }
+                paint_centered(ui, value_rect, galley);
             }
         }
 
+        paint_learn(
+            ui,
+            rect,
+            rect.width() / 2.0,
+            format!("knob_{id}_learn").into(),
+            parameter,
+        );
+
         response
     }
 }
 
-fn make_rects(
+/// paints a line of text in the middle of `rect`
+fn paint_centered(ui: &Ui, rect: Rect, galley: Arc<Galley>) {
+    ui.painter().galley(
+        pos2(
+            rect.center().x - galley.size().x / 2.0,
+            0.5f32.mul_add(-galley.size().y, rect.center().y),
+        ),
+        galley,
+        Color32::WHITE,
+    );
+}
+
+/// the rects of a knob-shaped widget and its label
+pub(super) struct KnobRects {
+    pub galley: Option<Arc<Galley>>,
+    pub full_rect: Rect,
+    pub response: Response,
+    /// where the knob itself goes
+    pub rect: Rect,
+    pub text_rect: Option<Rect>,
+    pub value_rect: Option<Rect>,
+}
+
+pub(super) fn make_rects(
     diameter: f32,
     label: Option<WidgetText>,
     description: Option<WidgetText>,
+    layout: &LabelLayout,
+    sense: Sense,
     ui: &mut Ui,
-) -> (
-    Option<std::sync::Arc<egui::Galley>>,
-    egui::Rect,
-    Response,
-    egui::Rect,
-    Option<egui::Rect>,
-) {
-    let mut desired_size = Vec2::splat(diameter + 5.0);
-    let galley = label.map_or_else(
-        || None,
-        |label| {
-            let galley = label.into_galley(
-                ui,
-                Some(egui::TextWrapMode::Extend),
-                desired_size.x,
-                TextStyle::Body,
-            );
-            let height_difference = galley.size().y + ui.spacing().item_spacing.y;
-            desired_size.y += height_difference;
-            desired_size.x = desired_size.x.max(galley.size().x);
-            Some(galley)
-        },
-    );
-    let (full_rect, mut response) = ui.allocate_exact_size(desired_size, Sense::click_and_drag());
+) -> KnobRects {
+    let knob_size = diameter + 5.0;
+    let shows_text = layout.position != LabelPosition::None;
+    let spacing = ui.spacing().item_spacing.y;
+
+    let wrap_mode = if layout.truncate {
+        TextWrapMode::Truncate
+    } else {
+        TextWrapMode::Extend
+    };
+    let galley = label
+        .filter(|_| shows_text)
+        .map(|label| label.into_galley(ui, Some(wrap_mode), knob_size, layout.text_style.clone()));
+    let text_height = galley
+        .as_ref()
+        .map_or(0.0, |galley| galley.size().y + spacing);
+    let value_height = if shows_text && layout.value_line {
+        ui.text_style_height(&layout.text_style) + spacing
+    } else {
+        0.0
+    };
+
+    let mut desired_size = Vec2::splat(knob_size);
+    desired_size.y += text_height + value_height;
+    if let Some(galley) = &galley {
+        desired_size.x = desired_size.x.max(galley.size().x);
+    }
+    let (full_rect, mut response) = ui.allocate_exact_size(desired_size, sense);
     if let Some(description) = description {
         response = response.on_hover_text_at_pointer(description);
     }
-    let (rect, text_rect) = if galley.is_some() {
-        let (rect, text_rect) = full_rect.split_top_bottom_at_y(full_rect.top() + (diameter) + 5.0);
-        (rect, Some(text_rect))
-    } else {
-        (full_rect, None)
+
+    // the label and the value line are stacked in that order on whichever side of the knob the label goes
+    let (rect, text_area) = match layout.position {
+        LabelPosition::Above => {
+            let (text_area, rect) = full_rect.split_top_bottom_at_y(full_rect.bottom() - knob_size);
+            (rect, text_area)
+        }
+        LabelPosition::Below | LabelPosition::None => {
+            full_rect.split_top_bottom_at_y(full_rect.top() + knob_size)
+        }
     };
-    (galley, full_rect, response, rect, text_rect)
+    let (text_rect, value_rect) = text_area.split_top_bottom_at_y(text_area.top() + text_height);
+
+    KnobRects {
+        galley,
+        full_rect,
+        response,
+        rect,
+        text_rect: (text_height > 0.0).then_some(text_rect),
+        value_rect: (value_height > 0.0).then_some(value_rect),
+    }
+}
+
+pub(super) fn pointer(
+    painter: &Painter,
+    center: Pos2,
+    direction: Vec2,
+    radius: f32,
+    background_radius: f32,
+) {
+    let first_point = center + direction * radius * 0.5;
+    let second_point = center + direction * radius;
+    painter.line_segment(
+        [first_point, second_point],
+        Stroke::new(background_radius * 0.15, Color32::WHITE),
+    );
+    painter.circle_filled(first_point, background_radius * 0.07, Color32::WHITE);
+    painter.circle_filled(second_point, background_radius * 0.07, Color32::WHITE);
 }
 
-fn star(painter: &Painter, angle: f32, diameter: f32) {
+fn star(painter: &Painter, center: Pos2, angle: f32, diameter: f32) {
     let angle = angle + 45.0;
     let (corner_1_sin, corner_1_cos) = angle.to_radians().sin_cos();
-    let corner_1 = painter.clip_rect().center()
+    let corner_1 = center
         + Vec2::new(
             (diameter * 0.2) * corner_1_sin,
             (diameter * 0.2) * corner_1_cos,
         );
     let (corner_2_sin, corner_2_cos) = (angle + 90.0).to_radians().sin_cos();
-    let corner_2 = painter.clip_rect().center()
+    let corner_2 = center
         + Vec2::new(
             (diameter * 0.2) * corner_2_sin,
             (diameter * 0.2) * corner_2_cos,
         );
     let (corner_3_sin, corner_3_cos) = (angle + 180.0).to_radians().sin_cos();
-    let corner_3 = painter.clip_rect().center()
+    let corner_3 = center
         + Vec2::new(
             (diameter * 0.2) * corner_3_sin,
             (diameter * 0.2) * corner_3_cos,
         );
     let (corner_4_sin, corner_4_cos) = (angle + 270.0).to_radians().sin_cos();
-    let corner_4 = painter.clip_rect().center()
+    let corner_4 = center
         + Vec2::new(
             (diameter * 0.2) * corner_4_sin,
             (diameter * 0.2) * corner_4_cos,
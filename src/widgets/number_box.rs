@@ -0,0 +1,281 @@
+use egui::{
+    text::{CCursor, CCursorRange},
+    vec2, Align, Align2, CursorIcon, Id, Key, Response, Sense, TextEdit, TextStyle, Ui, Widget,
+    WidgetInfo, WidgetText,
+};
+
+use crate::{colors::HIGHLIGHT_COL32, registry, undo, util::get_set::Operation};
+
+use super::{
+    apply_requested, begin_drag_gesture, begin_scroll_gesture, drag_normalized, end_drag_gesture,
+    end_scroll_gesture, fine_adjustment, format_value, gesture_open, get, scroll_normalized,
+    scrolled_to, set, Formatter,
+};
+
+type Parser<'a> = Box<dyn Fn(&str) -> Option<f32> + 'a>;
+
+pub const fn number_box<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>(
+    id: &str,
+    value: GetSet,
+    begin_set: Start,
+    end_set: End,
+) -> NumberBox<'_, GetSet, Start, End> {
+    NumberBox::new(id, value, begin_set, end_set)
+}
+
+/// A compact box showing a formatted value. Drag vertically to change it, or double click to type a new one
+#[must_use]
+pub struct NumberBox<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    description: Option<WidgetText>,
+    width: Option<f32>,
+    formatter: Option<Formatter<'a>>,
+    parser: Option<Parser<'a>>,
+    value: GetSet,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
+
+impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
+    NumberBox<'a, GetSet, Start, End>
+{
+    pub const fn new(id: &'a str, value: GetSet, begin_set: Start, end_set: End) -> Self {
+        Self {
+            id,
+            parameter: None,
+            description: None,
+            width: None,
+            formatter: None,
+            parser: None,
+            value,
+            read_only: false,
+            begin_set,
+            end_set,
+        }
+    }
+
+    /// Sets the description (flavor text shown in a tooltip)
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets a fixed width for the box. If this isn't set, the ``interact_size`` width is used instead
+    pub const fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets how the normalized value is displayed, units included (e.g. `120.0 BPM`)
+    pub fn formatter(mut self, formatter: impl Fn(f32) -> String + 'a) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// Sets how typed text is turned back into a normalized value. Returning `None` discards the edit
+    pub fn parser(mut self, parser: impl Fn(&str) -> Option<f32> + 'a) -> Self {
+        self.parser = Some(Box::new(parser));
+        self
+    }
+
+    fn format(&self, value: f32) -> String {
+        format_value(self.formatter.as_ref(), value)
+    }
+
+    fn parse(&self, text: &str) -> Option<f32> {
+        self.parser
+            .as_ref()
+            .map_or_else(|| text.trim().parse().ok(), |parser| parser(text))
+    }
+
+    /// registers the parameter, setting it if the registry asks for a new value and the box is `editable`
+    fn register(&mut self, ui: &Ui, response: &mut Response, editable: bool) {
+        let parameter = self.parameter.unwrap_or(self.id);
+        let value = get(&mut self.value);
+        let requested = registry::register(ui, self.id, parameter, response.rect, value, editable);
+        apply_requested(
+            response,
+            &mut self.value,
+            requested,
+            &self.begin_set,
+            &self.end_set,
+        );
+    }
+
+    /// shown instead of the box while the user is typing in a value
+    fn text_edit(&mut self, ui: &mut Ui, width: f32, editing_id: Id, text_id: Id) -> Response {
+        let mut text: String = ui.memory(|mem| mem.data.get_temp(text_id).unwrap_or_default());
+        let mut edit = TextEdit::singleline(&mut text)
+            .horizontal_align(Align::Center)
+            .desired_width(width)
+            .show(ui);
+        self.register(ui, &mut edit.response, !self.read_only);
+
+        if !edit.response.has_focus() && !edit.response.lost_focus() {
+            edit.state.cursor.set_char_range(Some(CCursorRange::two(
+                CCursor::new(0),
+                CCursor::new(text.chars().count()),
+            )));
+            edit.state.store(ui.ctx(), edit.response.id);
+            edit.response.request_focus();
+        }
+
+        if edit.response.lost_focus() {
+            if !ui.input(|input| input.key_pressed(Key::Escape)) {
+                if let Some(new_value) = self.parse(&text) {
+                    let new_value = new_value.clamp(0.0, 1.0);
+                    (self.begin_set)();
+                    let parameter = self.parameter.unwrap_or(self.id);
+                    undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+                    set(&mut self.value, new_value);
+                    undo::end_edit(ui.ctx(), parameter, new_value);
+                    (self.end_set)();
+                    edit.response.mark_changed();
+                }
+            }
+            ui.memory_mut(|mem| mem.data.insert_temp(editing_id, false));
+        } else {
+            ui.memory_mut(|mem| mem.data.insert_temp(text_id, text));
+        }
+
+        edit.response
+    }
+}
+
+impl<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
+    for NumberBox<'_, GetSet, Start, End>
+{
+    #[allow(clippy::too_many_lines)]
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let parameter = self.parameter.unwrap_or(id);
+        let editing_id = format!("number_box_{id}_editing").into();
+        let text_id = format!("number_box_{id}_text").into();
+        let scroll_gesture_id = format!("number_box_{id}_scroll").into();
+        let drag_gesture_id = format!("number_box_{id}_drag").into();
+
+        let width = self.width.unwrap_or_else(|| ui.spacing().interact_size.x);
+        let desired_size = vec2(width, ui.spacing().interact_size.y);
+
+        let interactive = ui.is_enabled() && !self.read_only;
+        if interactive && ui.memory(|mem| mem.data.get_temp(editing_id).unwrap_or(false)) {
+            return self.text_edit(ui, width, editing_id, text_id);
+        }
+
+        let sense = if interactive {
+            Sense::click_and_drag()
+        } else {
+            Sense::hover()
+        };
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, sense);
+        let editable = !self.read_only && !gesture_open(ui, drag_gesture_id, scroll_gesture_id);
+        self.register(ui, &mut response, editable);
+        if let Some(description) = self.description.take() {
+            response = response.on_hover_text_at_pointer(description);
+        }
+        let mut granular = false;
+
+        if response.double_clicked() {
+            let value = get(&mut self.value);
+            let text = self.format(value);
+            ui.memory_mut(|mem| {
+                mem.data.insert_temp(editing_id, true);
+                mem.data.insert_temp(text_id, text);
+            });
+        }
+
+        if interactive && response.hovered() {
+            granular = fine_adjustment(ui);
+            ui.ctx().set_cursor_icon(CursorIcon::ResizeVertical);
+        }
+
+        if end_scroll_gesture(ui, scroll_gesture_id, response.drag_started()) {
+            undo::end_edit(ui.ctx(), parameter, get(&mut self.value));
+            (self.end_set)();
+        }
+
+        if response.drag_started() {
+            begin_drag_gesture(ui, drag_gesture_id);
+            (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+        }
+
+        if response.dragged() {
+            ui.ctx().set_cursor_icon(CursorIcon::None);
+            granular = fine_adjustment(ui);
+
+            let delta = -response.drag_delta().y;
+            let new_value = drag_normalized(ui, get(&mut self.value), delta, granular);
+            set(&mut self.value, new_value);
+
+            response.mark_changed();
+        } else if interactive
+            && response.hovered()
+            && response
+                .ctx
+                .input(|input| input.raw_scroll_delta.length() > 0.0)
+        {
+            if begin_scroll_gesture(ui, scroll_gesture_id, parameter) {
+                (self.begin_set)();
+                undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+            }
+            let scroll_delta = response.ctx.input(|input| input.smooth_scroll_delta);
+            granular = fine_adjustment(ui);
+
+            let delta = -(scroll_delta.x + scroll_delta.y);
+            let new_value = scroll_normalized(ui, get(&mut self.value), delta, granular);
+            set(&mut self.value, new_value);
+            scrolled_to(ui, scroll_gesture_id, new_value);
+
+            response.mark_changed();
+        }
+
+        if end_drag_gesture(ui, drag_gesture_id, response.dragged()) {
+            undo::end_edit(ui.ctx(), parameter, get(&mut self.value));
+            (self.end_set)();
+        }
+
+        let value = get(&mut self.value);
+        let text = self.format(value);
+        response.widget_info(|| WidgetInfo::drag_value(interactive, f64::from(value)));
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact(&response);
+            let animated_granular = ui
+                .ctx()
+                .animate_bool(format!("number_box_{id}_granular").into(), granular);
+
+            ui.painter().rect(
+                rect.expand(visuals.expansion),
+                visuals.rounding,
+                visuals.weak_bg_fill,
+                visuals.bg_stroke,
+            );
+            ui.painter().text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                text,
+                TextStyle::Button.resolve(ui.style()),
+                visuals
+                    .text_color()
+                    .lerp_to_gamma(HIGHLIGHT_COL32, animated_granular),
+            );
+        }
+
+        response
+    }
+}
@@ -0,0 +1,395 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use egui::{
+    remap_clamp, vec2, Align2, Color32, Context, Id, Key, Pos2, Rect, Response, Rounding, Sense,
+    Stroke, TextStyle, Ui, Widget, WidgetInfo, WidgetText, WidgetType,
+};
+
+use crate::colors::{BACKGROUND, WIDGET_BACKGROUND_COL32};
+
+use super::button::light_color;
+
+const PIANOS_ID: &str = "cozy_ui_pianos";
+
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// the qwerty layout most daws use: the home row plays the white keys, the row above plays the black keys
+const KEYBOARD_NOTES: [(Key, u8); 17] = [
+    (Key::A, 0),
+    (Key::W, 1),
+    (Key::S, 2),
+    (Key::E, 3),
+    (Key::D, 4),
+    (Key::F, 5),
+    (Key::T, 6),
+    (Key::G, 7),
+    (Key::Y, 8),
+    (Key::H, 9),
+    (Key::U, 10),
+    (Key::J, 11),
+    (Key::K, 12),
+    (Key::O, 13),
+    (Key::L, 14),
+    (Key::P, 15),
+    (Key::Semicolon, 16),
+];
+
+/// the notes a piano is playing, kept outside egui's per-widget memory so they can be released when the piano
+/// stops being shown
+#[derive(Clone, Debug, Default)]
+struct PlayedNotes {
+    /// the frame the piano was last shown in
+    frame: u64,
+    active: Vec<u8>,
+    /// notes released while the piano wasn't shown, whose `note_off` hasn't been sent yet
+    released: Vec<u8>,
+}
+
+fn with_played_notes<R>(ctx: &Context, id: &str, f: impl FnOnce(&mut PlayedNotes) -> R) -> R {
+    ctx.data_mut(|data| {
+        let pianos: &mut HashMap<String, PlayedNotes> =
+            data.get_temp_mut_or_default(Id::new(PIANOS_ID));
+        f(pianos.entry(id.to_owned()).or_default())
+    })
+}
+
+/// Takes the notes of the piano `id` that were released because it stopped being shown, e.g. when its tab was closed.
+///
+/// Whoever takes the notes has to send their `note_off`, otherwise the piano sends it the next time it's shown
+#[must_use]
+pub fn take_released_notes(ctx: &Context, id: &str) -> Vec<u8> {
+    with_played_notes(ctx, id, |played| std::mem::take(&mut played.released))
+}
+
+/// releases the notes of every piano that wasn't shown last frame, since nothing else will.
+/// [`crate::setup`] runs this at the start of every frame
+pub(crate) fn release_hidden_pianos(ctx: &Context) {
+    let frame = ctx.frame_nr();
+    ctx.data_mut(|data| {
+        let pianos: &mut HashMap<String, PlayedNotes> =
+            data.get_temp_mut_or_default(Id::new(PIANOS_ID));
+        for played in pianos.values_mut() {
+            if played.frame + 1 < frame && !played.active.is_empty() {
+                let active = std::mem::take(&mut played.active);
+                played.released.extend(active);
+            }
+        }
+    });
+}
+
+pub const fn piano<NoteOn: FnMut(u8, f32), NoteOff: FnMut(u8)>(
+    id: &str,
+    note_on: NoteOn,
+    note_off: NoteOff,
+) -> Piano<'_, NoteOn, NoteOff> {
+    Piano::new(id, note_on, note_off)
+}
+
+#[must_use]
+pub struct Piano<'a, NoteOn: FnMut(u8, f32), NoteOff: FnMut(u8)> {
+    id: &'a str,
+    description: Option<WidgetText>,
+    octaves: RangeInclusive<i8>,
+    key_width: f32,
+    height: f32,
+    held_notes: &'a [u8],
+    computer_keyboard: bool,
+    keyboard_velocity: f32,
+    read_only: bool,
+    note_on: NoteOn,
+    note_off: NoteOff,
+}
+
+impl<'a, NoteOn: FnMut(u8, f32), NoteOff: FnMut(u8)> Piano<'a, NoteOn, NoteOff> {
+    pub const fn new(id: &'a str, note_on: NoteOn, note_off: NoteOff) -> Self {
+        Self {
+            id,
+            description: None,
+            octaves: 3..=4,
+            key_width: 16.0,
+            height: 64.0,
+            held_notes: &[],
+            computer_keyboard: false,
+            keyboard_velocity: 0.8,
+            read_only: false,
+            note_on,
+            note_off,
+        }
+    }
+
+    /// Sets the description (flavor text shown in a tooltip)
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the octaves shown on the keyboard. Octaves are numbered so that middle C (note 60) is C4
+    pub const fn octaves(mut self, octaves: RangeInclusive<i8>) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    /// Sets the width of a white key. Black keys are sized relative to this
+    pub const fn key_width(mut self, key_width: f32) -> Self {
+        self.key_width = key_width;
+        self
+    }
+
+    /// Sets the height of the white keys
+    pub const fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the notes held from outside the widget (host midi, sequencers), which are highlighted but don't fire callbacks
+    pub const fn held_notes(mut self, held_notes: &'a [u8]) -> Self {
+        self.held_notes = held_notes;
+        self
+    }
+
+    /// Lets the computer keyboard play notes while no other widget has keyboard focus. Z and X shift the octave
+    pub const fn computer_keyboard(mut self, enabled: bool) -> Self {
+        self.computer_keyboard = enabled;
+        self
+    }
+
+    /// Sets the velocity used for notes played with the computer keyboard
+    pub const fn keyboard_velocity(mut self, velocity: f32) -> Self {
+        self.keyboard_velocity = velocity;
+        self
+    }
+
+    /// Only shows the held notes, the keys can't be played with the pointer or the computer keyboard
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    fn note_range(&self) -> RangeInclusive<u8> {
+        let first = clamp_note((i16::from(*self.octaves.start()) + 1) * 12);
+        let last = clamp_note((i16::from(*self.octaves.end()) + 2) * 12 - 1);
+        first..=last.max(first)
+    }
+}
+
+impl<NoteOn: FnMut(u8, f32), NoteOff: FnMut(u8)> Widget for Piano<'_, NoteOn, NoteOff> {
+    #[allow(clippy::too_many_lines)]
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let notes = self.note_range();
+        let first_note = *notes.start();
+        let playable = ui.is_enabled() && !self.read_only;
+
+        let white_keys = notes.clone().filter(|note| !is_black(*note)).count();
+        #[allow(clippy::cast_precision_loss)]
+        let desired_size = vec2(self.key_width * white_keys as f32, self.height);
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::hover());
+
+        // black keys are registered last so they win hit testing where they overlap the white keys
+        let (black, white): (Vec<u8>, Vec<u8>) = notes.partition(|note| is_black(*note));
+        let keys: Vec<(u8, Rect, Response)> = white
+            .into_iter()
+            .chain(black)
+            .map(|note| {
+                let key_rect = key_rect(note, first_note, rect, self.key_width);
+                let key_response = ui.interact(
+                    key_rect,
+                    format!("piano_{id}_key_{note}").into(),
+                    if playable {
+                        Sense::click_and_drag()
+                    } else {
+                        Sense::hover()
+                    },
+                );
+                (note, key_rect, key_response)
+            })
+            .collect();
+
+        let mut pressed: Vec<(u8, f32)> = Vec::new();
+
+        if keys
+            .iter()
+            .any(|(_, _, key)| key.is_pointer_button_down_on())
+        {
+            // glissando: follow the pointer to whatever key is under it, even if the press started elsewhere
+            if let Some(pointer) = ui.input(|input| input.pointer.interact_pos()) {
+                if let Some((note, key_rect, _)) = keys
+                    .iter()
+                    .rev()
+                    .find(|(_, key_rect, _)| key_rect.contains(pointer))
+                {
+                    pressed.push((*note, velocity_at(*key_rect, pointer)));
+                }
+            }
+        }
+
+        if playable && self.computer_keyboard && !ui.ctx().wants_keyboard_input() {
+            let octave_id = format!("piano_{id}_keyboard_octave").into();
+            let mut octave: i8 = ui.memory(|mem| mem.data.get_temp(octave_id).unwrap_or(0));
+            let (octave_down, octave_up) =
+                ui.input(|input| (input.key_pressed(Key::Z), input.key_pressed(Key::X)));
+            if octave_up {
+                octave = octave.saturating_add(1);
+            }
+            if octave_down {
+                octave = octave.saturating_sub(1);
+            }
+            ui.memory_mut(|mem| mem.data.insert_temp(octave_id, octave));
+
+            let base = i16::from(first_note) + i16::from(octave) * 12;
+            ui.input(|input| {
+                for (key, offset) in KEYBOARD_NOTES {
+                    if !input.key_down(key) {
+                        continue;
+                    }
+                    let note = base + i16::from(offset);
+                    if !(0..=127).contains(&note) {
+                        continue;
+                    }
+                    let note = clamp_note(note);
+                    if !pressed.iter().any(|(pressed, _)| *pressed == note) {
+                        pressed.push((note, self.keyboard_velocity));
+                    }
+                }
+            });
+        }
+
+        let frame = ui.ctx().frame_nr();
+        let (previous, released) = with_played_notes(ui.ctx(), id, |played| {
+            played.frame = frame;
+            (played.active.clone(), std::mem::take(&mut played.released))
+        });
+        for note in released {
+            (self.note_off)(note);
+        }
+        for note in &previous {
+            if !pressed.iter().any(|(pressed, _)| pressed == note) {
+                (self.note_off)(*note);
+                response.mark_changed();
+            }
+        }
+        for (note, velocity) in &pressed {
+            if !previous.contains(note) {
+                (self.note_on)(*note, *velocity);
+                response.mark_changed();
+            }
+        }
+        let active: Vec<u8> = pressed.iter().map(|(note, _)| *note).collect();
+
+        if ui.is_rect_visible(rect) {
+            let outline = Stroke::new(
+                1.0,
+                Color32::from_rgb(BACKGROUND.0, BACKGROUND.1, BACKGROUND.2),
+            );
+            let label_font = TextStyle::Small.resolve(ui.style());
+
+            for (note, key_rect, _) in &keys {
+                let lit = active.contains(note) || self.held_notes.contains(note);
+                let animated_light = ui
+                    .ctx()
+                    .animate_bool(format!("piano_{id}_{note}_light").into(), lit);
+                let light_color = light_color(animated_light);
+
+                let (base_color, rounding) = if is_black(*note) {
+                    (
+                        WIDGET_BACKGROUND_COL32,
+                        Rounding {
+                            sw: 2.0,
+                            se: 2.0,
+                            ..Rounding::ZERO
+                        },
+                    )
+                } else {
+                    (
+                        Color32::WHITE,
+                        Rounding {
+                            sw: 3.0,
+                            se: 3.0,
+                            ..Rounding::ZERO
+                        },
+                    )
+                };
+                let fill = base_color.lerp_to_gamma(light_color, animated_light);
+                ui.painter().rect(*key_rect, rounding, fill, outline);
+
+                if note % 12 == 0 {
+                    ui.painter().text(
+                        key_rect.center_bottom() - vec2(0.0, 2.0),
+                        Align2::CENTER_BOTTOM,
+                        note_name(*note),
+                        label_font.clone(),
+                        WIDGET_BACKGROUND_COL32,
+                    );
+                }
+            }
+        }
+
+        for (note, _, key_response) in &keys {
+            let lit = active.contains(note) || self.held_notes.contains(note);
+            key_response.widget_info(|| {
+                WidgetInfo::selected(WidgetType::Button, true, lit, note_name(*note))
+            });
+        }
+
+        with_played_notes(ui.ctx(), id, |played| played.active = active);
+
+        for (_, _, key_response) in keys {
+            response |= key_response;
+        }
+
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
+        }
+
+        if let Some(cursor) = ui.visuals().interact_cursor {
+            if response.hovered {
+                ui.ctx().set_cursor_icon(cursor);
+            }
+        }
+
+        response
+    }
+}
+
+const fn is_black(note: u8) -> bool {
+    matches!(note % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+fn clamp_note(note: i16) -> u8 {
+    u8::try_from(note.clamp(0, 127)).unwrap_or_default()
+}
+
+fn note_name(note: u8) -> String {
+    format!(
+        "{}{}",
+        NOTE_NAMES[usize::from(note % 12)],
+        i16::from(note / 12) - 1
+    )
+}
+
+/// how many white keys there are between the first note and this one
+fn white_keys_before(note: u8, first_note: u8) -> usize {
+    (first_note..note).filter(|note| !is_black(*note)).count()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn key_rect(note: u8, first_note: u8, rect: Rect, key_width: f32) -> Rect {
+    let left = (white_keys_before(note, first_note) as f32).mul_add(key_width, rect.left());
+
+    if is_black(note) {
+        let black_width = key_width * 0.6;
+        Rect::from_min_size(
+            Pos2::new(left - black_width / 2.0, rect.top()),
+            vec2(black_width, rect.height() * 0.6),
+        )
+    } else {
+        Rect::from_min_size(Pos2::new(left, rect.top()), vec2(key_width, rect.height()))
+    }
+}
+
+/// like a real keyboard, pressing closer to the front of the key plays louder
+fn velocity_at(key_rect: Rect, pointer: Pos2) -> f32 {
+    remap_clamp(pointer.y, key_rect.y_range(), 0.1..=1.0)
+}
@@ -0,0 +1,333 @@
+use std::collections::BTreeSet;
+
+use egui::{
+    Align, Button, Frame, Id, InnerResponse, Layout, Response, RichText, ScrollArea, Sense,
+    TextEdit, Ui, Widget, WidgetInfo, WidgetText, WidgetType,
+};
+
+use crate::{
+    colors::{HIGHLIGHT_COL32, WIDGET_BACKGROUND_COL32},
+    presets::{Preset, PresetStore},
+};
+
+use super::button::{layout, paint};
+
+pub const fn preset_browser<'a, Store: PresetStore, Load: FnMut(&Preset, Vec<u8>)>(
+    id: &'a str,
+    store: &'a mut Store,
+    on_load: Load,
+) -> PresetBrowser<'a, Store, Load> {
+    PresetBrowser::new(id, store, on_load)
+}
+
+/// Browses the presets in a [`PresetStore`]. Whenever the user picks a preset its data is read from the store and handed to `on_load`
+#[must_use]
+pub struct PresetBrowser<'a, Store: PresetStore, Load: FnMut(&Preset, Vec<u8>)> {
+    id: &'a str,
+    store: &'a mut Store,
+    on_load: Load,
+    current: Option<&'a str>,
+    modified: bool,
+    height: f32,
+}
+
+impl<'a, Store: PresetStore, Load: FnMut(&Preset, Vec<u8>)> PresetBrowser<'a, Store, Load> {
+    pub const fn new(id: &'a str, store: &'a mut Store, on_load: Load) -> Self {
+        Self {
+            id,
+            store,
+            on_load,
+            current: None,
+            modified: false,
+            height: 200.0,
+        }
+    }
+
+    /// Sets the id of the loaded preset, which is highlighted and used as the starting point for the next/previous arrows
+    pub const fn current(mut self, current: Option<&'a str>) -> Self {
+        self.current = current;
+        self
+    }
+
+    /// Marks the loaded preset as edited since it was loaded
+    pub const fn modified(mut self, modified: bool) -> Self {
+        self.modified = modified;
+        self
+    }
+
+    /// Sets the height of the category and preset lists
+    pub const fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+}
+
+#[derive(Clone, Default)]
+struct BrowserState {
+    filter: String,
+    category: Vec<String>,
+    favorites_only: bool,
+    error: Option<String>,
+}
+
+enum Action {
+    Load(Preset),
+    Favorite(String, bool),
+    Refresh,
+}
+
+impl<Store: PresetStore, Load: FnMut(&Preset, Vec<u8>)> Widget for PresetBrowser<'_, Store, Load> {
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let state_id = Id::new(format!("preset_browser_{id}_state"));
+        let mut state: BrowserState =
+            ui.memory(|mem| mem.data.get_temp(state_id).unwrap_or_default());
+
+        let visible: Vec<Preset> = self
+            .store
+            .presets()
+            .iter()
+            .filter(|preset| state.matches(preset))
+            .cloned()
+            .collect();
+        let categories: BTreeSet<Vec<String>> = self
+            .store
+            .presets()
+            .iter()
+            .flat_map(|preset| {
+                (1..=preset.category.len()).map(|depth| preset.category[..depth].to_vec())
+            })
+            .collect();
+        let current_name = self
+            .current
+            .and_then(|current| {
+                self.store
+                    .presets()
+                    .iter()
+                    .find(|preset| preset.id == current)
+            })
+            .map_or_else(|| "no preset".to_owned(), |preset| preset.name.clone());
+
+        let InnerResponse {
+            inner: action,
+            mut response,
+        } = ui.push_id(format!("preset_browser_{id}"), |ui| {
+            let mut action = self.header(ui, &mut state, &visible, &current_name);
+
+            ui.add(
+                TextEdit::singleline(&mut state.filter)
+                    .hint_text("search")
+                    .desired_width(f32::INFINITY),
+            );
+
+            ui.horizontal_top(|ui| {
+                let category_width = ui.available_width() / 3.0;
+                list_frame().show(ui, |ui| {
+                    ui.set_width(category_width);
+                    category_list(ui, &mut state, &categories, self.height);
+                });
+                list_frame().show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    action = action.take().or_else(|| self.preset_list(ui, &visible));
+                });
+            });
+
+            if let Some(error) = &state.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+
+            action
+        });
+
+        match action {
+            Some(Action::Load(preset)) => match self.store.load(&preset.id) {
+                Ok(data) => {
+                    (self.on_load)(&preset, data);
+                    state.error = None;
+                    response.mark_changed();
+                }
+                Err(err) => state.error = Some(format!("couldn't load {}: {err}", preset.name)),
+            },
+            Some(Action::Favorite(id, favorite)) => {
+                state.error = self
+                    .store
+                    .set_favorite(&id, favorite)
+                    .err()
+                    .map(|err| err.to_string());
+            }
+            Some(Action::Refresh) => {
+                state.error = self.store.refresh().err().map(|err| err.to_string());
+            }
+            None => {}
+        }
+
+        ui.memory_mut(|mem| mem.data.insert_temp(state_id, state));
+
+        response
+    }
+}
+
+impl<Store: PresetStore, Load: FnMut(&Preset, Vec<u8>)> PresetBrowser<'_, Store, Load> {
+    /// the arrows, the loaded preset's name and the list options
+    fn header(
+        &self,
+        ui: &mut Ui,
+        state: &mut BrowserState,
+        visible: &[Preset],
+        current_name: &str,
+    ) -> Option<Action> {
+        let id = self.id;
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            if ui.button("⏴").on_hover_text("previous preset").clicked() {
+                action = step(visible, self.current, false).map(Action::Load);
+            }
+            if ui.button("⏵").on_hover_text("next preset").clicked() {
+                action = step(visible, self.current, true).map(Action::Load);
+            }
+
+            if self.modified {
+                ui.label(RichText::new(format!("{current_name} *")).color(HIGHLIGHT_COL32))
+                    .on_hover_text("modified since it was loaded");
+            } else {
+                ui.label(current_name);
+            }
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.button("🔄").on_hover_text("rescan presets").clicked() {
+                    action = Some(Action::Refresh);
+                }
+                // drawn like a toggle, but it's not a parameter, so it stays out of the registry and the undo history
+                let (galley, desired_size, button_padding) =
+                    layout(ui, WidgetText::from("★"), true);
+                let (rect, response) = ui.allocate_at_least(desired_size, Sense::click());
+                let response = response.on_hover_text("only show favorites");
+                if response.clicked() {
+                    state.favorites_only = !state.favorites_only;
+                }
+                let favorites_only = state.favorites_only;
+                response.widget_info(|| {
+                    WidgetInfo::selected(
+                        WidgetType::Button,
+                        true,
+                        favorites_only,
+                        "only show favorites",
+                    )
+                });
+
+                let animated_value = ui.ctx().animate_bool(
+                    format!("preset_browser_{id}_favorites_light").into(),
+                    favorites_only,
+                );
+                paint(ui, rect, &response, galley, button_padding, animated_value);
+            });
+        });
+
+        action
+    }
+
+    fn preset_list(&self, ui: &mut Ui, visible: &[Preset]) -> Option<Action> {
+        let mut action = None;
+
+        ScrollArea::vertical()
+            .id_source("presets")
+            .max_height(self.height)
+            .show(ui, |ui| {
+                if visible.is_empty() {
+                    ui.weak("no presets");
+                }
+                for preset in visible {
+                    ui.horizontal(|ui| {
+                        let (star, color) = if preset.favorite {
+                            ("★", HIGHLIGHT_COL32)
+                        } else {
+                            ("☆", ui.visuals().weak_text_color())
+                        };
+                        if ui
+                            .add(Button::new(RichText::new(star).color(color)).frame(false))
+                            .on_hover_text("favorite")
+                            .clicked()
+                        {
+                            action = Some(Action::Favorite(preset.id.clone(), !preset.favorite));
+                        }
+
+                        let selected = self.current == Some(preset.id.as_str());
+                        if ui
+                            .selectable_label(selected, preset.name.as_str())
+                            .clicked()
+                        {
+                            action = Some(Action::Load(preset.clone()));
+                        }
+                    });
+                }
+            });
+
+        action
+    }
+}
+
+impl BrowserState {
+    fn matches(&self, preset: &Preset) -> bool {
+        let filter = self.filter.trim().to_lowercase();
+        (!self.favorites_only || preset.favorite)
+            && preset.in_category(&self.category)
+            && (filter.is_empty() || preset.name.to_lowercase().contains(&filter))
+    }
+}
+
+fn category_list(
+    ui: &mut Ui,
+    state: &mut BrowserState,
+    categories: &BTreeSet<Vec<String>>,
+    height: f32,
+) {
+    ScrollArea::vertical()
+        .id_source("categories")
+        .max_height(height)
+        .show(ui, |ui| {
+            if ui
+                .selectable_label(state.category.is_empty(), "all")
+                .clicked()
+            {
+                state.category.clear();
+            }
+            for category in categories {
+                ui.horizontal(|ui| {
+                    #[allow(clippy::cast_precision_loss)]
+                    ui.add_space(category.len() as f32 * ui.spacing().indent);
+                    let selected = state.category == *category;
+                    let label = category.last().map_or("", String::as_str);
+                    if ui.selectable_label(selected, label).clicked() {
+                        state.category.clone_from(category);
+                    }
+                });
+            }
+        });
+}
+
+fn list_frame() -> Frame {
+    Frame::none()
+        .fill(WIDGET_BACKGROUND_COL32)
+        .rounding(3.0)
+        .inner_margin(4.0)
+}
+
+/// the preset after (or before) `current` in `presets`, wrapping around at either end
+fn step(presets: &[Preset], current: Option<&str>, forward: bool) -> Option<Preset> {
+    let len = presets.len();
+    if len == 0 {
+        return None;
+    }
+
+    let position =
+        current.and_then(|current| presets.iter().position(|preset| preset.id == current));
+    let index = match (position, forward) {
+        (Some(position), true) => (position + 1) % len,
+        (Some(position), false) => (position + len - 1) % len,
+        (None, true) => 0,
+        (None, false) => len - 1,
+    };
+
+    presets.get(index).cloned()
+}
@@ -0,0 +1,469 @@
+use std::sync::Arc;
+
+use egui::{
+    epaint::PathShape, pos2, vec2, Galley, NumExt, PopupCloseBehavior, Rect, Response, Sense,
+    Stroke, TextStyle, Ui, Vec2, Widget, WidgetInfo, WidgetText, WidgetType,
+};
+
+use crate::{registry, undo, util::get_set::Operation};
+
+use super::{apply_requested, button::paint_light, get, set, variant_at, variant_value};
+
+pub const fn segmented<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()>(
+    id: &'a str,
+    variants: &'a [(T, &'a str)],
+    value: GetSet,
+    begin_set: Start,
+    end_set: End,
+) -> Segmented<'a, T, GetSet, Start, End> {
+    Segmented::new(id, variants, value, begin_set, end_set)
+}
+
+pub const fn dropdown<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()>(
+    id: &'a str,
+    variants: &'a [(T, &'a str)],
+    value: GetSet,
+    begin_set: Start,
+    end_set: End,
+) -> Dropdown<'a, T, GetSet, Start, End> {
+    Dropdown::new(id, variants, value, begin_set, end_set)
+}
+
+/// A row of segments, one per variant, with the selected one lit up
+#[must_use]
+pub struct Segmented<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    description: Option<WidgetText>,
+    variants: &'a [(T, &'a str)],
+    value: GetSet,
+    small: bool,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
+
+impl<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()>
+    Segmented<'a, T, GetSet, Start, End>
+{
+    pub const fn new(
+        id: &'a str,
+        variants: &'a [(T, &'a str)],
+        value: GetSet,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameter: None,
+            description: None,
+            variants,
+            value,
+            small: false,
+            read_only: false,
+            begin_set,
+            end_set,
+        }
+    }
+
+    /// Sets the description (flavor text shown in a tooltip)
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub const fn small(mut self) -> Self {
+        self.small = true;
+        self
+    }
+}
+
+impl<T: PartialEq + Copy, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()> Widget
+    for Segmented<'_, T, GetSet, Start, End>
+{
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let button_padding = button_padding(ui, self.small);
+        let galleys = variant_galleys(ui, self.variants);
+        let segment_size = option_size(ui, &galleys, button_padding, self.small);
+
+        #[allow(clippy::cast_precision_loss)]
+        let desired_size = vec2(segment_size.x * self.variants.len() as f32, segment_size.y);
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::hover());
+        let sense = if self.read_only {
+            Sense::hover()
+        } else {
+            Sense::click()
+        };
+
+        let parameter = self.parameter.unwrap_or(id);
+        let mut current = get(&mut self.value);
+        let registered = variant_value(self.variants, &current);
+        let requested = registry::register(ui, id, parameter, rect, registered, !self.read_only)
+            .and_then(|value| variant_at(self.variants, value));
+        if let Some(variant) = apply_requested(
+            &mut response,
+            &mut self.value,
+            requested,
+            &self.begin_set,
+            &self.end_set,
+        ) {
+            current = variant;
+        }
+        let mut segments = Vec::with_capacity(self.variants.len());
+        for (index, ((variant, _), galley)) in self.variants.iter().zip(galleys).enumerate() {
+            #[allow(clippy::cast_precision_loss)]
+            let segment_rect = Rect::from_min_size(
+                rect.min + vec2(segment_size.x * index as f32, 0.0),
+                segment_size,
+            );
+            let mut segment_response = ui.interact(
+                segment_rect,
+                format!("segmented_{id}_{index}").into(),
+                sense,
+            );
+
+            if segment_response.clicked() && *variant != current {
+                (self.begin_set)();
+                undo::begin_edit(ui.ctx(), parameter, variant_value(self.variants, &current));
+                set(&mut self.value, *variant);
+                undo::end_edit(ui.ctx(), parameter, variant_value(self.variants, variant));
+                (self.end_set)();
+                current = *variant;
+                segment_response.mark_changed();
+            }
+
+            let selected = *variant == current;
+            segment_response.widget_info(|| {
+                WidgetInfo::selected(WidgetType::RadioButton, true, selected, galley.text())
+            });
+            segments.push((segment_response, galley));
+        }
+
+        for (segment_response, _) in &segments {
+            response |= segment_response.clone();
+        }
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact(&response);
+            ui.painter().rect(
+                rect.expand(visuals.expansion),
+                visuals.rounding,
+                visuals.weak_bg_fill,
+                visuals.bg_stroke,
+            );
+
+            for (index, ((variant, _), (segment_response, galley))) in
+                self.variants.iter().zip(segments).enumerate()
+            {
+                let animated_value = ui.ctx().animate_bool(
+                    format!("segmented_{id}_{index}_light").into(),
+                    *variant == current,
+                );
+
+                if index > 0 {
+                    ui.painter().vline(
+                        segment_response.rect.left(),
+                        segment_response.rect.y_range().shrink(3.0),
+                        ui.visuals().widgets.noninteractive.bg_stroke,
+                    );
+                }
+
+                paint_option(
+                    ui,
+                    &segment_response,
+                    galley,
+                    button_padding,
+                    animated_value,
+                );
+            }
+        }
+
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
+        }
+
+        response
+    }
+}
+
+/// A button showing the selected variant that opens a popup list of all variants when clicked
+#[must_use]
+pub struct Dropdown<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()> {
+    id: &'a str,
+    parameter: Option<&'a str>,
+    description: Option<WidgetText>,
+    variants: &'a [(T, &'a str)],
+    value: GetSet,
+    small: bool,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+}
+
+impl<'a, T, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()>
+    Dropdown<'a, T, GetSet, Start, End>
+{
+    pub const fn new(
+        id: &'a str,
+        variants: &'a [(T, &'a str)],
+        value: GetSet,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameter: None,
+            description: None,
+            variants,
+            value,
+            small: false,
+            read_only: false,
+            begin_set,
+            end_set,
+        }
+    }
+
+    /// Sets the description (flavor text shown in a tooltip)
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub const fn small(mut self) -> Self {
+        self.small = true;
+        self
+    }
+}
+
+impl<T: PartialEq + Copy, GetSet: FnMut(Operation<T>) -> T, Start: Fn(), End: Fn()> Widget
+    for Dropdown<'_, T, GetSet, Start, End>
+{
+    #[allow(clippy::too_many_lines)]
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let button_padding = button_padding(ui, self.small);
+        let galleys = variant_galleys(ui, self.variants);
+        let option_size = option_size(ui, &galleys, button_padding, self.small);
+        let icon_width = option_size.y * 0.5;
+
+        let desired_size = option_size + vec2(icon_width, 0.0);
+        let sense = if self.read_only {
+            Sense::hover()
+        } else {
+            Sense::click()
+        };
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, sense);
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
+        }
+
+        let popup_id = format!("dropdown_{id}_popup").into();
+        if response.clicked() {
+            ui.memory_mut(|mem| mem.toggle_popup(popup_id));
+        }
+        let open = ui.memory(|mem| mem.is_popup_open(popup_id));
+
+        let parameter = self.parameter.unwrap_or(id);
+        let mut current = get(&mut self.value);
+        let registered = variant_value(self.variants, &current);
+        let requested = registry::register(ui, id, parameter, rect, registered, !self.read_only)
+            .and_then(|value| variant_at(self.variants, value));
+        if let Some(variant) = apply_requested(
+            &mut response,
+            &mut self.value,
+            requested,
+            &self.begin_set,
+            &self.end_set,
+        ) {
+            current = variant;
+        }
+        let selected = self
+            .variants
+            .iter()
+            .zip(&galleys)
+            .find_map(|((variant, _), galley)| (*variant == current).then(|| galley.clone()));
+        response.widget_info(|| {
+            WidgetInfo::labeled(
+                WidgetType::ComboBox,
+                true,
+                selected.as_ref().map_or("", |galley| galley.text()),
+            )
+        });
+
+        let animated_value = ui
+            .ctx()
+            .animate_bool(format!("dropdown_{id}_light").into(), open);
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact(&response);
+            ui.painter().rect(
+                rect.expand(visuals.expansion),
+                visuals.rounding,
+                visuals.weak_bg_fill,
+                visuals.bg_stroke,
+            );
+
+            if let Some(galley) = selected {
+                paint_option(ui, &response, galley, button_padding, animated_value);
+            }
+
+            let icon_rect = Rect::from_center_size(
+                pos2(
+                    rect.right() - button_padding.x - icon_width / 2.0,
+                    rect.center().y,
+                ),
+                Vec2::splat(icon_width * 0.6),
+            );
+            ui.painter().add(PathShape::convex_polygon(
+                vec![
+                    icon_rect.left_top(),
+                    icon_rect.right_top(),
+                    icon_rect.center_bottom(),
+                ],
+                visuals.text_color(),
+                Stroke::NONE,
+            ));
+        }
+
+        let mut changed = false;
+        egui::popup_below_widget(
+            ui,
+            popup_id,
+            &response,
+            PopupCloseBehavior::CloseOnClick,
+            |ui| {
+                ui.spacing_mut().item_spacing.y = 0.0;
+                for (index, ((variant, _), galley)) in self.variants.iter().zip(galleys).enumerate()
+                {
+                    let (_, mut option_response) = ui.allocate_exact_size(
+                        vec2(desired_size.x.at_least(ui.available_width()), option_size.y),
+                        sense,
+                    );
+
+                    if option_response.clicked() && *variant != current {
+                        (self.begin_set)();
+                        undo::begin_edit(
+                            ui.ctx(),
+                            parameter,
+                            variant_value(self.variants, &current),
+                        );
+                        set(&mut self.value, *variant);
+                        undo::end_edit(ui.ctx(), parameter, variant_value(self.variants, variant));
+                        (self.end_set)();
+                        option_response.mark_changed();
+                        changed = true;
+                    }
+
+                    let selected = *variant == current;
+                    option_response.widget_info(|| {
+                        WidgetInfo::selected(
+                            WidgetType::SelectableLabel,
+                            true,
+                            selected,
+                            galley.text(),
+                        )
+                    });
+
+                    let animated_value = ui
+                        .ctx()
+                        .animate_bool(format!("dropdown_{id}_{index}_light").into(), selected);
+                    if ui.is_rect_visible(option_response.rect) {
+                        if option_response.hovered() {
+                            let visuals = ui.style().interact(&option_response);
+                            ui.painter().rect_filled(
+                                option_response.rect,
+                                visuals.rounding,
+                                visuals.weak_bg_fill,
+                            );
+                        }
+                        paint_option(ui, &option_response, galley, button_padding, animated_value);
+                    }
+                }
+            },
+        );
+        if changed {
+            response.mark_changed();
+        }
+
+        if let Some(cursor) = ui.visuals().interact_cursor {
+            if response.hovered && response.sense.click {
+                ui.ctx().set_cursor_icon(cursor);
+            }
+        }
+
+        response
+    }
+}
+
+fn button_padding(ui: &Ui, small: bool) -> Vec2 {
+    let mut button_padding = ui.spacing().button_padding;
+    if small {
+        button_padding.y = 0.0;
+    }
+    button_padding
+}
+
+fn variant_galleys<T>(ui: &Ui, variants: &[(T, &str)]) -> Vec<Arc<Galley>> {
+    variants
+        .iter()
+        .map(|(_, label)| {
+            WidgetText::from(*label).into_galley(ui, None, f32::INFINITY, TextStyle::Button)
+        })
+        .collect()
+}
+
+/// every option gets the same size so the widget doesn't jump around when the selection changes
+fn option_size(ui: &Ui, galleys: &[Arc<Galley>], button_padding: Vec2, small: bool) -> Vec2 {
+    let mut size = galleys
+        .iter()
+        .fold(Vec2::ZERO, |size, galley| size.max(galley.size()));
+    size.x += 10.0;
+    size += 2.0 * button_padding;
+    if !small {
+        size.y = size.y.at_least(ui.spacing().interact_size.y);
+    }
+    size
+}
+
+/// paints an option the way [`super::button::Toggle`] paints itself: light on the left, then the text
+fn paint_option(
+    ui: &Ui,
+    response: &Response,
+    galley: Arc<Galley>,
+    button_padding: Vec2,
+    animated_value: f32,
+) {
+    let rect = response.rect;
+    let visuals = ui.style().interact(response);
+    let text_pos = pos2(
+        rect.min.x + button_padding.x + 10.0,
+        0.5f32.mul_add(-galley.size().y, rect.center().y),
+    );
+    paint_light(ui.painter(), rect, button_padding, animated_value);
+    ui.painter().galley(text_pos, galley, visuals.text_color());
+}
@@ -2,9 +2,12 @@ use egui::{
     lerp, pos2, remap_clamp, vec2, Color32, Rect, Rounding, Sense, Ui, Vec2, Widget, WidgetText,
 };
 
-use crate::{colors::HIGHLIGHT_COL32, util::get_set::Operation};
+use crate::{colors::HIGHLIGHT_COL32, registry, undo, util::get_set::Operation};
 
-use super::{get, set};
+use super::{
+    apply_requested, drag_gesture_open, format_value, get, paint_learn, parameter_menu,
+    reset_requested, set, value_readout, AutomationState, Formatter,
+};
 
 pub const fn slider<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>(
     id: &str,
@@ -18,9 +21,14 @@ pub const fn slider<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()
 #[must_use]
 pub struct Slider<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> {
     id: &'a str,
+    parameter: Option<&'a str>,
     description: Option<WidgetText>,
+    formatter: Option<Formatter<'a>>,
     width: Option<f32>,
     default: Option<f32>,
+    automation: AutomationState,
+    locked: bool,
+    read_only: bool,
     value: GetSet,
     begin_set: Start,
     end_set: End,
@@ -32,9 +40,14 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
     pub const fn new(id: &'a str, value: GetSet, begin_set: Start, end_set: End) -> Self {
         Self {
             id,
+            parameter: None,
             description: None,
+            formatter: None,
             width: None,
             default: None,
+            automation: AutomationState::None,
+            locked: false,
+            read_only: false,
             value,
             begin_set,
             end_set,
@@ -47,36 +60,98 @@ impl<'a, GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()>
         self
     }
 
+    /// Sets how the value is shown in the readout bubble, units included (e.g. `-6.0 dB`)
+    pub fn formatter(mut self, formatter: impl Fn(f32) -> String + 'a) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// Sets the id of the parameter this widget controls, see [`crate::registry`]
+    pub const fn parameter_id(mut self, parameter: &'a str) -> Self {
+        self.parameter = Some(parameter);
+        self
+    }
+
     /// Sets a fixed width for the slider. If this isn't set, the ``slider_width`` is used instead
     pub const fn width(mut self, width: f32) -> Self {
         self.width = Some(width);
         self
     }
 
-    /// Sets the value the slider will reset to when the user double clicks on the slider
+    /// Sets the value the slider resets to when the user performs the reset gesture (see [`crate::interaction::InteractionSettings::reset_gesture`])
     pub const fn default_value(mut self, default: f32) -> Self {
         self.default = Some(default);
         self
     }
+
+    /// Shows the host's automation state for the parameter
+    pub const fn automation(mut self, automation: AutomationState) -> Self {
+        self.automation = automation;
+        self
+    }
+
+    /// Locks the slider while automation is playing back. It still shows the value but ignores the pointer
+    pub const fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
 }
 
 impl<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
     for Slider<'_, GetSet, Start, End>
 {
+    #[allow(clippy::too_many_lines)]
     fn ui(mut self, ui: &mut Ui) -> egui::Response {
         let id = self.id;
+        let parameter = self.parameter.unwrap_or(id);
 
         let desired_size = vec2(self.width.unwrap_or(ui.spacing().slider_width), 15.0);
-        let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::click_and_drag());
+        let interactive = ui.is_enabled() && !self.locked && !self.read_only;
+        let sense = if interactive {
+            Sense::click_and_drag()
+        } else {
+            Sense::hover()
+        };
+        let (rect, mut response) = ui.allocate_exact_size(desired_size, sense);
         if let Some(description) = self.description {
             response = response.on_hover_text_at_pointer(description);
         }
+        if self.locked {
+            response = response.on_hover_text_at_pointer("locked by automation");
+        }
+
+        let value = get(&mut self.value);
+        let requested = registry::register(
+            ui,
+            id,
+            parameter,
+            rect,
+            value,
+            !self.locked
+                && !self.read_only
+                && !drag_gesture_open(ui, format!("slider_{id}_begin_set").into()),
+        );
+        apply_requested(
+            &mut response,
+            &mut self.value,
+            requested,
+            &self.begin_set,
+            &self.end_set,
+        );
 
         let handle_radius = rect.height() / 2.5;
         let handle_radius_aspect = handle_radius * 0.5;
         let position_range = rect.x_range().shrink(handle_radius);
 
-        if let Some(pointer_position) = response.interact_pointer_pos() {
+        // the secondary button opens the context menu instead
+        let primary_down = ui.input(|input| input.pointer.primary_down());
+        if let Some(pointer_position) = response.interact_pointer_pos().filter(|_| primary_down) {
             if ui.memory(|mem| {
                 !mem.data
                     .get_temp(format!("slider_{id}_begin_set").into())
@@ -87,6 +162,7 @@ impl<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
                         .insert_temp(format!("slider_{id}_begin_set").into(), true);
                 });
                 (self.begin_set)();
+                undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
             }
             let pointer_position = pointer_position.x;
             let normalized = remap_clamp(pointer_position, position_range, 0.0..=1.0);
@@ -105,18 +181,30 @@ impl<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
                 mem.data
                     .insert_temp(format!("slider_{id}_begin_set").into(), false);
             });
+            undo::end_edit(ui.ctx(), parameter, get(&mut self.value));
             (self.end_set)();
         }
 
-        if let Some(default) = self.default {
-            if response.double_clicked() {
-                (self.begin_set)();
-                set(&mut self.value, default);
-                response.mark_changed();
-                (self.end_set)();
-            }
+        let menu_value = parameter_menu(&response, parameter, get(&mut self.value), self.default);
+        let reset_default = self.default.filter(|_| reset_requested(ui, &response));
+        if let Some(new_value) = menu_value.or(reset_default) {
+            (self.begin_set)();
+            undo::begin_edit(ui.ctx(), parameter, get(&mut self.value));
+            set(&mut self.value, new_value);
+            undo::end_edit(ui.ctx(), parameter, new_value);
+            response.mark_changed();
+            (self.end_set)();
         }
 
+        let readout_value = get(&mut self.value);
+        value_readout(
+            ui,
+            &response,
+            format!("slider_{id}_readout").into(),
+            response.is_pointer_button_down_on(),
+            || format_value(self.formatter.as_ref(), readout_value),
+        );
+
         if ui.is_rect_visible(rect) {
             let visuals = ui.style().interact(&response);
             let painter = ui.painter_at(rect);
@@ -137,9 +225,18 @@ impl<GetSet: FnMut(Operation<f32>) -> f32, Start: Fn(), End: Fn()> Widget
             );
             let v = v + Vec2::splat(expansion);
             let handle_rect = Rect::from_center_size(center, 2.0 * v);
-            painter.rect_filled(handle_rect, Rounding::same(2.0), Color32::WHITE);
+            let handle_color = self.automation.color().unwrap_or(Color32::WHITE);
+            painter.rect_filled(handle_rect, Rounding::same(2.0), handle_color);
         }
 
+        paint_learn(
+            ui,
+            rect.shrink(3.0),
+            1.5,
+            format!("slider_{id}_learn").into(),
+            parameter,
+        );
+
         response
     }
 }
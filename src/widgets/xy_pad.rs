@@ -0,0 +1,308 @@
+use egui::{
+    lerp, pos2, Color32, Painter, Pos2, Rect, Response, Rounding, Sense, Stroke, Ui, Vec2, Widget,
+    WidgetText,
+};
+
+use crate::{
+    colors::{HIGHLIGHT_COL32, MODULATION_COL32, PURPLE_COL32, WIDGET_BACKGROUND_COL32},
+    interaction::interaction_settings,
+    registry, undo,
+    util::get_set::Operation,
+};
+
+use super::{
+    begin_drag_gesture, drag_gesture_open, end_drag_gesture, fine_adjustment, format_value, get,
+    reset_requested, set, value_readout, Formatter,
+};
+
+pub const fn xy_pad<
+    GetSetX: FnMut(Operation<f32>) -> f32,
+    GetSetY: FnMut(Operation<f32>) -> f32,
+    Start: Fn(),
+    End: Fn(),
+>(
+    id: &str,
+    size: Vec2,
+    x_value: GetSetX,
+    y_value: GetSetY,
+    begin_set: Start,
+    end_set: End,
+) -> XyPad<'_, GetSetX, GetSetY, Start, End> {
+    XyPad::new(id, size, x_value, y_value, begin_set, end_set)
+}
+
+/// A pad that edits two parameters at once. `begin_set` and `end_set` bracket gestures on both parameters together.
+///
+/// Unless they're given ids with [`XyPad::parameter_ids`], the parameters are known as `{id}_x` and `{id}_y`,
+/// see [`crate::registry`]
+#[must_use]
+pub struct XyPad<
+    'a,
+    GetSetX: FnMut(Operation<f32>) -> f32,
+    GetSetY: FnMut(Operation<f32>) -> f32,
+    Start: Fn(),
+    End: Fn(),
+> {
+    id: &'a str,
+    parameters: Option<(&'a str, &'a str)>,
+    description: Option<WidgetText>,
+    size: Vec2,
+    x_value: GetSetX,
+    y_value: GetSetY,
+    read_only: bool,
+    begin_set: Start,
+    end_set: End,
+    default: Option<(f32, f32)>,
+    modulated: Option<(f32, f32)>,
+    formatters: (Option<Formatter<'a>>, Option<Formatter<'a>>),
+}
+
+impl<
+        'a,
+        GetSetX: FnMut(Operation<f32>) -> f32,
+        GetSetY: FnMut(Operation<f32>) -> f32,
+        Start: Fn(),
+        End: Fn(),
+    > XyPad<'a, GetSetX, GetSetY, Start, End>
+{
+    pub const fn new(
+        id: &'a str,
+        size: Vec2,
+        x_value: GetSetX,
+        y_value: GetSetY,
+        begin_set: Start,
+        end_set: End,
+    ) -> Self {
+        Self {
+            id,
+            parameters: None,
+            description: None,
+            size,
+            x_value,
+            y_value,
+            read_only: false,
+            begin_set,
+            end_set,
+            default: None,
+            modulated: None,
+            formatters: (None, None),
+        }
+    }
+
+    /// Sets the description (flavor text shown in a tooltip)
+    pub fn description(mut self, description: impl Into<WidgetText>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the ids of the parameters this widget controls, see [`crate::registry`]
+    pub const fn parameter_ids(mut self, x: &'a str, y: &'a str) -> Self {
+        self.parameters = Some((x, y));
+        self
+    }
+
+    /// Shows the value without letting the user change it, see [`crate::widgets`]
+    pub const fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets the values both parameters reset to when the user performs the reset gesture (see [`crate::interaction::InteractionSettings::reset_gesture`])
+    pub const fn default_value(mut self, x: f32, y: f32) -> Self {
+        self.default = Some((x, y));
+        self
+    }
+
+    /// Sets how the values are shown in the readout bubble, units included (e.g. `440 Hz`)
+    pub fn formatters(
+        mut self,
+        x: impl Fn(f32) -> String + 'a,
+        y: impl Fn(f32) -> String + 'a,
+    ) -> Self {
+        self.formatters = (Some(Box::new(x)), Some(Box::new(y)));
+        self
+    }
+
+    /// Sets the modulated position, drawn as a ghost dot
+    pub const fn modulated_value(mut self, x: f32, y: f32) -> Self {
+        self.modulated = Some((x, y));
+        self
+    }
+}
+
+impl<
+        GetSetX: FnMut(Operation<f32>) -> f32,
+        GetSetY: FnMut(Operation<f32>) -> f32,
+        Start: Fn(),
+        End: Fn(),
+    > Widget for XyPad<'_, GetSetX, GetSetY, Start, End>
+{
+    #[allow(clippy::too_many_lines)]
+    fn ui(mut self, ui: &mut Ui) -> Response {
+        let id = self.id;
+        let (x_parameter, y_parameter) = self.parameters.map_or_else(
+            || (format!("{id}_x"), format!("{id}_y")),
+            |(x, y)| (x.to_owned(), y.to_owned()),
+        );
+        let drag_gesture_id = format!("xy_pad_{id}_drag").into();
+        let interactive = ui.is_enabled() && !self.read_only;
+        let sense = if interactive {
+            Sense::click_and_drag()
+        } else {
+            Sense::hover()
+        };
+        let (rect, mut response) = ui.allocate_exact_size(self.size, sense);
+        if let Some(description) = self.description {
+            response = response.on_hover_text_at_pointer(description);
+        }
+
+        let x = get(&mut self.x_value);
+        let y = get(&mut self.y_value);
+        let editable = !self.read_only && !drag_gesture_open(ui, drag_gesture_id);
+        let pending_x = registry::register(ui, &format!("{id}_x"), &x_parameter, rect, x, editable);
+        let pending_y = registry::register(ui, &format!("{id}_y"), &y_parameter, rect, y, editable);
+        if pending_x.is_some() || pending_y.is_some() {
+            (self.begin_set)();
+            if let Some(x) = pending_x {
+                set(&mut self.x_value, x);
+            }
+            if let Some(y) = pending_y {
+                set(&mut self.y_value, y);
+            }
+            (self.end_set)();
+            response.mark_changed();
+        }
+        let mut granular = false;
+        let hovered = interactive && (response.hovered() || response.dragged());
+
+        let handle_radius = rect.width().min(rect.height()) * 0.05;
+        let pad_rect = rect.shrink(handle_radius);
+
+        if let Some((default_x, default_y)) = self.default {
+            if reset_requested(ui, &response) {
+                (self.begin_set)();
+                undo::begin_edit(ui.ctx(), &x_parameter, get(&mut self.x_value));
+                undo::begin_edit(ui.ctx(), &y_parameter, get(&mut self.y_value));
+                set(&mut self.x_value, default_x);
+                set(&mut self.y_value, default_y);
+                undo::end_edit(ui.ctx(), &x_parameter, default_x);
+                undo::end_edit(ui.ctx(), &y_parameter, default_y);
+                response.mark_changed();
+                (self.end_set)();
+            }
+        }
+
+        if hovered {
+            granular = fine_adjustment(ui);
+        }
+
+        if response.drag_started() {
+            begin_drag_gesture(ui, drag_gesture_id);
+            (self.begin_set)();
+            undo::begin_edit(ui.ctx(), &x_parameter, get(&mut self.x_value));
+            undo::begin_edit(ui.ctx(), &y_parameter, get(&mut self.y_value));
+        }
+
+        if response.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::None);
+            let drag_delta = response.drag_delta();
+            granular = fine_adjustment(ui);
+            let size_scale = if granular {
+                interaction_settings(ui.ctx()).fine_factor.max(1.0)
+            } else {
+                1.0
+            };
+
+            let new_x = get(&mut self.x_value) + drag_delta.x / (pad_rect.width() * size_scale);
+            let new_y = get(&mut self.y_value) - drag_delta.y / (pad_rect.height() * size_scale);
+            set(&mut self.x_value, new_x.clamp(0.0, 1.0));
+            set(&mut self.y_value, new_y.clamp(0.0, 1.0));
+
+            response.mark_changed();
+        }
+
+        if end_drag_gesture(ui, drag_gesture_id, response.dragged()) {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Default);
+            undo::end_edit(ui.ctx(), &x_parameter, get(&mut self.x_value));
+            undo::end_edit(ui.ctx(), &y_parameter, get(&mut self.y_value));
+            (self.end_set)();
+        }
+
+        let (readout_x, readout_y) = (get(&mut self.x_value), get(&mut self.y_value));
+        value_readout(
+            ui,
+            &response,
+            format!("xy_pad_{id}_readout").into(),
+            response.dragged(),
+            || {
+                format!(
+                    "{}, {}",
+                    format_value(self.formatters.0.as_ref(), readout_x),
+                    format_value(self.formatters.1.as_ref(), readout_y)
+                )
+            },
+        );
+
+        if ui.is_rect_visible(rect) {
+            let painter = ui.painter_at(rect);
+
+            let animated_granular = ui
+                .ctx()
+                .animate_bool(format!("xy_pad_{id}_granular").into(), granular);
+            let animated_hover = ui
+                .ctx()
+                .animate_bool(format!("xy_pad_{id}_hover").into(), hovered);
+
+            painter.rect(
+                rect,
+                Rounding::same(3.0),
+                WIDGET_BACKGROUND_COL32,
+                Stroke::new(2.0, PURPLE_COL32.gamma_multiply(animated_hover)),
+            );
+
+            let position = to_position(pad_rect, get(&mut self.x_value), get(&mut self.y_value));
+
+            let crosshair_stroke = Stroke::new(
+                1.0,
+                HIGHLIGHT_COL32.gamma_multiply(0.5f32.mul_add(animated_granular, 0.5)),
+            );
+            painter.hline(rect.x_range(), position.y, crosshair_stroke);
+            painter.vline(position.x, rect.y_range(), crosshair_stroke);
+
+            if let Some((modulated_x, modulated_y)) = self.modulated {
+                draw_handle(
+                    &painter,
+                    to_position(pad_rect, modulated_x, modulated_y),
+                    handle_radius,
+                    MODULATION_COL32.gamma_multiply(0.6),
+                );
+            }
+
+            draw_handle(&painter, position, handle_radius, Color32::WHITE);
+        }
+
+        if let Some(cursor) = ui.visuals().interact_cursor {
+            if interactive && response.hovered() && !response.dragged() {
+                ui.ctx().set_cursor_icon(cursor);
+            }
+        }
+
+        response
+    }
+}
+
+fn to_position(pad_rect: Rect, x: f32, y: f32) -> Pos2 {
+    pos2(
+        lerp(pad_rect.x_range(), x),
+        lerp(pad_rect.bottom()..=pad_rect.top(), y),
+    )
+}
+
+fn draw_handle(painter: &Painter, position: Pos2, radius: f32, color: Color32) {
+    painter.circle_filled(position, radius, color);
+    painter.circle_stroke(
+        position,
+        radius,
+        Stroke::new(radius * 0.3, WIDGET_BACKGROUND_COL32),
+    );
+}